@@ -0,0 +1,111 @@
+//! Everything generated here goes to `OUT_DIR`, never to the source tree:
+//!
+//! - `include/shuffle.h`, `include/shuffle_cxx.h` and `include/rust/cxx.h`
+//! - `pkgconfig/shuffle.pc` and `cmake/Shuffle/*.cmake`
+//! - `soname`, the file name the shared library is installed under
+//!
+//! `cargo xtask install` copies them to an install prefix with the libraries, and
+//! `cargo xtask header` updates the committed `include/shuffle.h`.
+
+use std::path::{Path, PathBuf};
+
+/// Generates `<out>/include/shuffle.h`
+fn generate_header(crate_dir: &Path, out_dir: &Path) {
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("invalid cbindgen.toml");
+
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("could not generate the C header")
+        .write_to_file(out_dir.join("include/shuffle.h"));
+}
+
+/// Compiles the C++ side of the cxx bridge into the library and copies its header,
+/// with the `rust/cxx.h` it includes, to `<out>/include`
+fn build_cxx_bridge(out_dir: &Path) {
+    cxx_build::bridge("src/bridge.rs")
+        .std("c++14")
+        .compile("shuffle_cxx");
+
+    let generated = out_dir.join("cxxbridge/include");
+    for (src, dest) in [
+        (generated.join("cpp_adapter/src/bridge.rs.h"), "shuffle_cxx.h"),
+        (generated.join("rust/cxx.h"), "rust/cxx.h"),
+    ] {
+        let dest = out_dir.join("include").join(dest);
+        std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        std::fs::copy(&src, &dest).unwrap();
+    }
+}
+
+/// Renders a template of `pkg/`, replacing the `@NAME@` variables
+fn render(crate_dir: &Path, template: &str, dest: PathBuf, vars: &[(&str, &str)]) {
+    let mut content = std::fs::read_to_string(crate_dir.join("pkg").join(template))
+        .unwrap_or_else(|e| panic!("could not read {}: {}", template, e));
+    for (name, value) in vars {
+        content = content.replace(&format!("@{}@", name), value);
+    }
+    std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+    std::fs::write(&dest, content)
+        .unwrap_or_else(|e| panic!("could not write {}: {}", dest.display(), e));
+}
+
+fn main() {
+    let crate_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    generate_header(&crate_dir, &out_dir);
+    build_cxx_bridge(&out_dir);
+
+    // The SONAME carries the major version, which is the C API version: programs
+    // linked against an older API refuse to load an incompatible library.
+    let lib_name = "cpp_adapter";
+    let version = std::env::var("CARGO_PKG_VERSION").unwrap();
+    let api_version = std::env::var("CARGO_PKG_VERSION_MAJOR").unwrap();
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let soname = match target_os.as_str() {
+        "macos" | "ios" => format!("lib{}.{}.dylib", lib_name, api_version),
+        "windows" => format!("{}.dll", lib_name),
+        _ => format!("lib{}.so.{}", lib_name, api_version),
+    };
+    match target_os.as_str() {
+        "macos" | "ios" => println!(
+            "cargo:rustc-cdylib-link-arg=-Wl,-install_name,@rpath/{}",
+            soname
+        ),
+        "windows" => {}
+        _ => println!("cargo:rustc-cdylib-link-arg=-Wl,-soname,{}", soname),
+    }
+    println!("cargo:rustc-env=SHUFFLE_SONAME={}", soname);
+    std::fs::write(out_dir.join("soname"), &soname).unwrap();
+    // the C smoke test compiles for the same target
+    println!(
+        "cargo:rustc-env=SHUFFLE_TARGET={}",
+        std::env::var("TARGET").unwrap()
+    );
+
+    // pkg-config and CMake files, for the prefix given to `cargo xtask install`
+    let prefix = std::env::var("SHUFFLE_PREFIX").unwrap_or("/usr/local".to_string());
+    let vars = [
+        ("PREFIX", prefix.as_str()),
+        ("VERSION", version.as_str()),
+        ("API_VERSION", api_version.as_str()),
+        ("LIB_NAME", lib_name),
+        ("SONAME", soname.as_str()),
+    ];
+    render(&crate_dir, "shuffle.pc.in", out_dir.join("pkgconfig/shuffle.pc"), &vars);
+    for file in ["ShuffleConfig.cmake", "ShuffleConfigVersion.cmake"] {
+        render(
+            &crate_dir,
+            &format!("{}.in", file),
+            out_dir.join("cmake/Shuffle").join(file),
+            &vars,
+        );
+    }
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=pkg");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=SHUFFLE_PREFIX");
+}
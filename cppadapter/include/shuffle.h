@@ -0,0 +1,168 @@
+/* shuffle C API, see cppadapter/src/lib.rs */
+
+#ifndef SHUFFLE_H
+#define SHUFFLE_H
+
+/* Generated by cbindgen from cppadapter, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Version of the C API, bumped on every incompatible change of `shuffle.h`. It is
+// the major version of the crate and of the shared library SONAME.
+#define SHUFFLE_API_VERSION 4
+
+// Status code returned by every fallible function of the C API
+typedef enum ShuffleStatus {
+  // Success
+  SHUFFLE_STATUS_OK = 0,
+  // A required pointer argument was null
+  SHUFFLE_STATUS_NULL_POINTER = 1,
+  // A string argument was not valid UTF-8
+  SHUFFLE_STATUS_INVALID_UTF8 = 2,
+  // The configuration was rejected, see `shuffle_last_error_message`
+  SHUFFLE_STATUS_INVALID_CONFIG = 3,
+  // No password satisfying the constraints was found
+  SHUFFLE_STATUS_CONSTRAINTS_UNSATISFIABLE = 4,
+  // The OS entropy source or the RNG health tests failed
+  SHUFFLE_STATUS_ENTROPY_UNAVAILABLE = 5,
+  // Unexpected failure, e.g. a panic caught at the FFI boundary
+  SHUFFLE_STATUS_INTERNAL = 6,
+} ShuffleStatus;
+
+// Why a configuration is invalid, one value per `PasswordConfigError` variant
+typedef enum ShuffleConfigError {
+  // The configuration is valid
+  SHUFFLE_CONFIG_ERROR_NONE = 0,
+  SHUFFLE_CONFIG_ERROR_ZERO_LENGTH = 1,
+  SHUFFLE_CONFIG_ERROR_NO_CHARACTER_SETS_ENABLED = 2,
+  SHUFFLE_CONFIG_ERROR_LENGTH_TOO_SHORT_FOR_SETS = 3,
+  SHUFFLE_CONFIG_ERROR_NOT_ENOUGH_AVAILABLE_CHARACTERS = 4,
+  SHUFFLE_CONFIG_ERROR_PIN_LENGTH_TOO_SHORT = 5,
+  SHUFFLE_CONFIG_ERROR_ZERO_WORDS = 6,
+  SHUFFLE_CONFIG_ERROR_EMPTY_WORDLIST = 7,
+  SHUFFLE_CONFIG_ERROR_MIN_GREATER_THAN_MAX = 8,
+  SHUFFLE_CONFIG_ERROR_LENGTH_TOO_SHORT_FOR_MINIMUMS = 9,
+  SHUFFLE_CONFIG_ERROR_LENGTH_TOO_LONG_FOR_MAXIMUMS = 10,
+  SHUFFLE_CONFIG_ERROR_ZERO_CONSTRAINT_LIMIT = 11,
+  SHUFFLE_CONFIG_ERROR_UNAVOIDABLE_REPETITION = 12,
+  SHUFFLE_CONFIG_ERROR_UNAVOIDABLE_SEQUENCE = 13,
+  SHUFFLE_CONFIG_ERROR_EMPTY_CHARACTER_SET = 14,
+  SHUFFLE_CONFIG_ERROR_UNKNOWN_CHARACTER_SET = 15,
+  SHUFFLE_CONFIG_ERROR_MIN_COUNT_TOO_HIGH_FOR_OCCURRENCES = 16,
+  SHUFFLE_CONFIG_ERROR_OVERLAPPING_CAPPED_CHARACTER_SET = 17,
+  SHUFFLE_CONFIG_ERROR_NOT_ENOUGH_DISTINCT_CHARACTERS = 18,
+} ShuffleConfigError;
+
+// Password configuration, see `shuffle_config_default`
+typedef struct ShuffleConfig {
+  // Length of the password to generate
+  size_t length;
+  bool uppercase;
+  bool lowercase;
+  bool digits;
+  bool braces;
+  bool punctuation;
+  bool quotes;
+  bool dashes;
+  bool math;
+  bool logograms;
+  // UTF-8 characters never used in the password, may be null
+  const char *excluded;
+  // UTF-8 characters added to the alphabet, may be null
+  const char *included;
+} ShuffleConfig;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns `SHUFFLE_API_VERSION` of the library, to check it against the header
+uint32_t shuffle_api_version(void);
+
+// Returns the version of shuffle_core. The string belongs to the library.
+const char *shuffle_version(void);
+
+// Returns the version and the RNG backend of shuffle_core. The string belongs to
+// the library.
+const char *shuffle_info(void);
+
+// Returns the default configuration: 10 characters among lowercase and uppercase
+// letters and digits
+struct ShuffleConfig shuffle_config_default(void);
+
+// Generates a password into `*out`, to be freed with `shuffle_string_free`.
+//
+// `*out` is set to null on failure.
+//
+// # Safety
+// `config` must be null or point to a valid `ShuffleConfig` whose strings are null
+// or nul-terminated. `out` must be null or valid for writes.
+enum ShuffleStatus shuffle_generate(const struct ShuffleConfig *config, char **out);
+
+// Generates `count` passwords across `threads` threads (all the cores when 0), all
+// different when `unique` is set.
+//
+// `*out` receives an array of `*out_count` strings, to be freed with
+// `shuffle_batch_free`. It is set to null on failure.
+//
+// # Safety
+// `config` must be null or point to a valid `ShuffleConfig` whose strings are null
+// or nul-terminated. `out` and `out_count` must be null or valid for writes.
+enum ShuffleStatus shuffle_generate_batch(const struct ShuffleConfig *config,
+                                          size_t count,
+                                          size_t threads,
+                                          bool unique,
+                                          char ***out,
+                                          size_t *out_count);
+
+// Wipes and frees the passwords of `shuffle_generate_batch` and their array. Does
+// nothing on null.
+//
+// # Safety
+// `passwords` must be null or an array returned by `shuffle_generate_batch`, not
+// freed yet, and `count` its number of passwords.
+void shuffle_batch_free(char **passwords, size_t count);
+
+// Validates a configuration.
+//
+// Returns `SHUFFLE_STATUS_INVALID_CONFIG` when it is invalid: `*error` (unless
+// null) receives the reason and `shuffle_last_error_message` describes it.
+// `*error` is `SHUFFLE_CONFIG_ERROR_NONE` when the configuration is valid or
+// could not be read.
+//
+// # Safety
+// `config` must be null or point to a valid `ShuffleConfig` whose strings are null
+// or nul-terminated. `error` must be null or valid for writes.
+enum ShuffleStatus shuffle_config_validate(const struct ShuffleConfig *config,
+                                           enum ShuffleConfigError *error);
+
+// Computes into `*out` the entropy in bits of a password generated with a valid
+// configuration, see `PasswordConfig::entropy_bits`.
+//
+// # Safety
+// `config` must be null or point to a valid `ShuffleConfig` whose strings are null
+// or nul-terminated. `out` must be null or valid for writes.
+enum ShuffleStatus shuffle_config_entropy_bits(const struct ShuffleConfig *config, double *out);
+
+// Wipes and frees a string returned by the library. Does nothing on null.
+//
+// # Safety
+// `ptr` must be null or a string returned by the library and not freed yet.
+void shuffle_string_free(char *ptr);
+
+// Returns the message of the last failed call on the calling thread, or null if
+// no call failed yet.
+//
+// The string belongs to the library and stays valid until the next failed call
+// on the same thread. Do not free it.
+const char *shuffle_last_error_message(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SHUFFLE_H */
@@ -0,0 +1,165 @@
+//! C++ API of shuffle, generated by cxx in `include/shuffle_cxx.h`.
+//!
+//! ```cpp
+//! #include "rust/cxx.h" // for rust::Error
+//! #include "shuffle_cxx.h"
+//!
+//! rust::Box<shuffle::PasswordConfig> config = shuffle::new_password_config(20);
+//! config->set_braces(true);
+//! config->set_excluded("0O1Il5S");
+//! try {
+//!     rust::String password = shuffle::generate(*config);
+//! } catch (const rust::Error &e) {
+//!     // e.what() describes the invalid configuration
+//! }
+//! ```
+//!
+//! Unlike the C API, the signatures are checked on both sides at compile time. The
+//! functions returning `Result` throw `rust::Error` in C++. The bridge is only
+//! available in the static library: the shared library exports the C API alone.
+
+use shuffle_core::config::PasswordConfigError;
+use shuffle_core::{charset, try_generate, GenerateError};
+
+#[cxx::bridge(namespace = "shuffle")]
+mod ffi {
+    extern "Rust" {
+        /// Password configuration, see `shuffle_core::PasswordConfig`
+        type PasswordConfig;
+
+        /// Creates a configuration with the default character sets, throws if
+        /// `length` is 0
+        fn new_password_config(length: usize) -> Result<Box<PasswordConfig>>;
+
+        fn length(self: &PasswordConfig) -> usize;
+        fn set_length(self: &mut PasswordConfig, length: usize);
+        fn set_uppercase(self: &mut PasswordConfig, include: bool);
+        fn set_lowercase(self: &mut PasswordConfig, include: bool);
+        fn set_digits(self: &mut PasswordConfig, include: bool);
+        fn set_braces(self: &mut PasswordConfig, include: bool);
+        fn set_punctuation(self: &mut PasswordConfig, include: bool);
+        fn set_quotes(self: &mut PasswordConfig, include: bool);
+        fn set_dashes(self: &mut PasswordConfig, include: bool);
+        fn set_math(self: &mut PasswordConfig, include: bool);
+        fn set_logograms(self: &mut PasswordConfig, include: bool);
+        /// Characters never used in the password
+        fn set_excluded(self: &mut PasswordConfig, excluded: &str);
+        /// Characters added to the alphabet
+        fn set_included(self: &mut PasswordConfig, included: &str);
+
+        /// Throws a `rust::Error` describing the problem if the configuration is invalid
+        fn validate(self: &PasswordConfig) -> Result<()>;
+
+        /// Entropy of a generated password in bits, throws if the configuration is
+        /// invalid
+        fn entropy_bits(self: &PasswordConfig) -> Result<f64>;
+
+        /// Generates a password, throws if the configuration is invalid or the
+        /// constraints can not be satisfied
+        fn generate(config: &PasswordConfig) -> Result<String>;
+    }
+}
+
+pub struct PasswordConfig(shuffle_core::PasswordConfig);
+
+fn new_password_config(length: usize) -> Result<Box<PasswordConfig>, PasswordConfigError> {
+    Ok(Box::new(PasswordConfig(shuffle_core::PasswordConfig::new(length)?)))
+}
+
+impl PasswordConfig {
+    fn length(&self) -> usize {
+        self.0.length
+    }
+
+    fn set_length(&mut self, length: usize) {
+        self.0.length = length;
+    }
+
+    fn set_uppercase(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::UPPERCASE, include);
+    }
+
+    fn set_lowercase(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::LOWERCASE, include);
+    }
+
+    fn set_digits(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::DIGITS, include);
+    }
+
+    fn set_braces(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::BRACES, include);
+    }
+
+    fn set_punctuation(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::PUNCTUATION, include);
+    }
+
+    fn set_quotes(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::QUOTES, include);
+    }
+
+    fn set_dashes(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::DASHES, include);
+    }
+
+    fn set_math(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::MATH, include);
+    }
+
+    fn set_logograms(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::LOGOGRAMS, include);
+    }
+
+    fn set_excluded(&mut self, excluded: &str) {
+        self.0.excluded = excluded.to_string();
+    }
+
+    fn set_included(&mut self, included: &str) {
+        self.0.included = included.to_string();
+    }
+
+    fn validate(&self) -> Result<(), PasswordConfigError> {
+        self.0.validate()
+    }
+
+    fn entropy_bits(&self) -> Result<f64, PasswordConfigError> {
+        self.0.validate()?;
+        Ok(self.0.entropy_bits())
+    }
+}
+
+/// The password is copied into a `rust::String`, which is not wiped when dropped
+fn generate(config: &PasswordConfig) -> Result<String, GenerateError> {
+    let password = try_generate(&config.0)?;
+    Ok(password.expose_secret().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let mut config = new_password_config(20).unwrap();
+        config.set_uppercase(false);
+        config.set_excluded("abc");
+        let password = generate(&config).unwrap();
+
+        assert_eq!(password.len(), 20);
+        assert!(!password.contains(|c: char| "abcABC".contains(c)));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let mut config = new_password_config(12).unwrap();
+        config.set_uppercase(false);
+        config.set_lowercase(false);
+        config.set_digits(false);
+
+        assert!(new_password_config(0).is_err());
+        assert!(config.validate().is_err());
+        assert!(config.entropy_bits().is_err());
+        assert!(generate(&config).is_err());
+    }
+}
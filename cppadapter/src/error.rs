@@ -0,0 +1,174 @@
+use shuffle_core::config::PasswordConfigError;
+use shuffle_core::GenerateError;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, UnwindSafe};
+
+/// Status code returned by every fallible function of the C API
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShuffleStatus {
+    /// Success
+    Ok = 0,
+    /// A required pointer argument was null
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// The configuration was rejected, see `shuffle_last_error_message`
+    InvalidConfig = 3,
+    /// No password satisfying the constraints was found
+    ConstraintsUnsatisfiable = 4,
+    /// The OS entropy source or the RNG health tests failed
+    EntropyUnavailable = 5,
+    /// Unexpected failure, e.g. a panic caught at the FFI boundary
+    Internal = 6,
+}
+
+/// Why a configuration is invalid, one value per `PasswordConfigError` variant
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShuffleConfigError {
+    /// The configuration is valid
+    None = 0,
+    ZeroLength = 1,
+    NoCharacterSetsEnabled = 2,
+    LengthTooShortForSets = 3,
+    NotEnoughAvailableCharacters = 4,
+    PinLengthTooShort = 5,
+    ZeroWords = 6,
+    EmptyWordlist = 7,
+    MinGreaterThanMax = 8,
+    LengthTooShortForMinimums = 9,
+    LengthTooLongForMaximums = 10,
+    ZeroConstraintLimit = 11,
+    UnavoidableRepetition = 12,
+    UnavoidableSequence = 13,
+    EmptyCharacterSet = 14,
+    UnknownCharacterSet = 15,
+    MinCountTooHighForOccurrences = 16,
+    OverlappingCappedCharacterSet = 17,
+    NotEnoughDistinctCharacters = 18,
+}
+
+impl From<&PasswordConfigError> for ShuffleConfigError {
+    fn from(e: &PasswordConfigError) -> Self {
+        match e {
+            PasswordConfigError::ZeroLength => Self::ZeroLength,
+            PasswordConfigError::NoCharacterSetsEnabled => Self::NoCharacterSetsEnabled,
+            PasswordConfigError::LengthTooShortForSets { .. } => Self::LengthTooShortForSets,
+            PasswordConfigError::NotEnoughAvailableCharacters { .. } => {
+                Self::NotEnoughAvailableCharacters
+            }
+            PasswordConfigError::PinLengthTooShort => Self::PinLengthTooShort,
+            PasswordConfigError::ZeroWords => Self::ZeroWords,
+            PasswordConfigError::EmptyWordlist => Self::EmptyWordlist,
+            PasswordConfigError::MinGreaterThanMax { .. } => Self::MinGreaterThanMax,
+            PasswordConfigError::LengthTooShortForMinimums { .. } => {
+                Self::LengthTooShortForMinimums
+            }
+            PasswordConfigError::LengthTooLongForMaximums { .. } => {
+                Self::LengthTooLongForMaximums
+            }
+            PasswordConfigError::ZeroConstraintLimit => Self::ZeroConstraintLimit,
+            PasswordConfigError::NotEnoughDistinctCharacters { .. } => {
+                Self::NotEnoughDistinctCharacters
+            }
+            PasswordConfigError::UnavoidableRepetition { .. } => Self::UnavoidableRepetition,
+            PasswordConfigError::UnavoidableSequence { .. } => Self::UnavoidableSequence,
+            PasswordConfigError::EmptyCharacterSet { .. } => Self::EmptyCharacterSet,
+            PasswordConfigError::UnknownCharacterSet { .. } => Self::UnknownCharacterSet,
+            PasswordConfigError::MinCountTooHighForOccurrences { .. } => {
+                Self::MinCountTooHighForOccurrences
+            }
+            PasswordConfigError::OverlappingCappedCharacterSet { .. } => {
+                Self::OverlappingCappedCharacterSet
+            }
+        }
+    }
+}
+
+/// A failure of a call, recorded for `shuffle_last_error_message`
+#[derive(Debug)]
+pub(crate) struct FfiError {
+    pub status: ShuffleStatus,
+    pub message: String,
+    /// Why the configuration is invalid, `None` for the other failures
+    pub config_error: ShuffleConfigError,
+}
+
+impl FfiError {
+    pub fn new(status: ShuffleStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            config_error: ShuffleConfigError::None,
+        }
+    }
+}
+
+impl From<PasswordConfigError> for FfiError {
+    fn from(e: PasswordConfigError) -> Self {
+        Self {
+            config_error: (&e).into(),
+            ..Self::new(ShuffleStatus::InvalidConfig, e.to_string())
+        }
+    }
+}
+
+impl From<GenerateError> for FfiError {
+    fn from(e: GenerateError) -> Self {
+        let status = match e {
+            GenerateError::InvalidConfig(e) => return e.into(),
+            GenerateError::InvalidPattern(_) => ShuffleStatus::InvalidConfig,
+            GenerateError::ConstraintsUnsatisfiable { .. }
+            | GenerateError::NotEnoughUniquePasswords { .. } => {
+                ShuffleStatus::ConstraintsUnsatisfiable
+            }
+            GenerateError::EntropyUnavailable(_) => ShuffleStatus::EntropyUnavailable,
+            #[cfg(feature = "health-tests")]
+            GenerateError::HealthTestFailed(_) => ShuffleStatus::EntropyUnavailable,
+            GenerateError::Io(_) => ShuffleStatus::Internal,
+        };
+        Self::new(status, e.to_string())
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    // a message can not contain a nul byte, drop it rather than losing the message
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Runs the body of an exported function: records the error message of a failure
+/// and turns a panic into `ShuffleStatus::Internal` instead of unwinding into C.
+pub(crate) fn ffi_call<F>(f: F) -> ShuffleStatus
+where
+    F: FnOnce() -> Result<(), FfiError> + UnwindSafe,
+{
+    let error = match catch_unwind(f) {
+        Ok(Ok(())) => return ShuffleStatus::Ok,
+        Ok(Err(e)) => e,
+        Err(_) => FfiError::new(ShuffleStatus::Internal, "panic in shuffle"),
+    };
+    set_last_error(&error.message);
+    error.status
+}
+
+/// Returns the message of the last failed call on the calling thread, or null if
+/// no call failed yet.
+///
+/// The string belongs to the library and stays valid until the next failed call
+/// on the same thread. Do not free it.
+#[no_mangle]
+pub extern "C" fn shuffle_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
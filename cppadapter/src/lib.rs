@@ -1,53 +1,460 @@
+//! C API of shuffle, used by the Qt GUI.
+//!
+//! `include/shuffle.h` is generated from this crate by cbindgen at build time, never
+//! edit it by hand. Every fallible function returns a `ShuffleStatus` and records a
+//! message for `shuffle_last_error_message`; strings handed to the caller are freed
+//! with `shuffle_string_free`.
+
+mod bridge;
+mod error;
+
+use error::{ffi_call, FfiError};
+use shuffle_core::{
+    charset, generate_batch, getinfo, getversion, try_generate, BatchOptions, PasswordConfig,
+};
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
-use shuffle_core::{PasswordConfig, generate_password, getversion};
+use std::sync::OnceLock;
+use zeroize::Zeroize;
+
+pub use error::{shuffle_last_error_message, ShuffleConfigError, ShuffleStatus};
+
+/// Version of the C API, bumped on every incompatible change of `shuffle.h`. It is
+/// the major version of the crate and of the shared library SONAME.
+pub const SHUFFLE_API_VERSION: u32 = 4;
+
+/// Password configuration, see `shuffle_config_default`
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ShuffleConfig {
+    /// Length of the password to generate
+    pub length: usize,
+    pub uppercase: bool,
+    pub lowercase: bool,
+    pub digits: bool,
+    pub braces: bool,
+    pub punctuation: bool,
+    pub quotes: bool,
+    pub dashes: bool,
+    pub math: bool,
+    pub logograms: bool,
+    /// UTF-8 characters never used in the password, may be null
+    pub excluded: *const c_char,
+    /// UTF-8 characters added to the alphabet, may be null
+    pub included: *const c_char,
+}
+
+/// Returns `SHUFFLE_API_VERSION` of the library, to check it against the header
+#[no_mangle]
+pub extern "C" fn shuffle_api_version() -> u32 {
+    SHUFFLE_API_VERSION
+}
 
+/// Returns the version of shuffle_core. The string belongs to the library.
 #[no_mangle]
-pub extern "C" fn get_version() -> *mut c_char {
-    rust_to_c_string(getversion().to_string())
+pub extern "C" fn shuffle_version() -> *const c_char {
+    static VERSION: OnceLock<CString> = OnceLock::new();
+    VERSION
+        .get_or_init(|| CString::new(getversion()).unwrap_or_default())
+        .as_ptr()
 }
 
+/// Returns the version and the RNG backend of shuffle_core. The string belongs to
+/// the library.
 #[no_mangle]
-pub extern "C" fn get_random(length: u8,
-                             uppercase: bool,
-                             lowercase:bool,
-                             digits:bool,
-                             braces:bool,
-                             punctuation:bool,
-                             quotes:bool,
-                             dashes:bool,
-                             math:bool,
-                             logograms:bool,
-                             avoid: *mut c_char,
-                             also: *mut c_char) -> *mut c_char {
+pub extern "C" fn shuffle_info() -> *const c_char {
+    static INFO: OnceLock<CString> = OnceLock::new();
+    INFO.get_or_init(|| CString::new(getinfo()).unwrap_or_default())
+        .as_ptr()
+}
 
-    let config = PasswordConfig::new(length as usize).unwrap()
-        .with_uppercase(uppercase)
-        .with_lowercase(lowercase)
-        .with_digits(digits)
-        .with_braces(braces)
-        .with_punctuation(punctuation)
-        .with_quotes(quotes)
-        .with_dashes(dashes)
-        .with_math(math)
-        .with_logograms(logograms)
-        .excluded(c_to_rust_string(avoid).unwrap())
-        .included(c_to_rust_string(also).unwrap());
+/// Returns the default configuration: 10 characters among lowercase and uppercase
+/// letters and digits
+#[no_mangle]
+pub extern "C" fn shuffle_config_default() -> ShuffleConfig {
+    let config = PasswordConfig::default();
+    let enabled = |name| config.charsets.is_enabled(name);
+    ShuffleConfig {
+        length: config.length,
+        uppercase: enabled(charset::UPPERCASE),
+        lowercase: enabled(charset::LOWERCASE),
+        digits: enabled(charset::DIGITS),
+        braces: enabled(charset::BRACES),
+        punctuation: enabled(charset::PUNCTUATION),
+        quotes: enabled(charset::QUOTES),
+        dashes: enabled(charset::DASHES),
+        math: enabled(charset::MATH),
+        logograms: enabled(charset::LOGOGRAMS),
+        excluded: std::ptr::null(),
+        included: std::ptr::null(),
+    }
+}
 
-    let password = generate_password(&config);
-    rust_to_c_string(password)
+/// Generates a password into `*out`, to be freed with `shuffle_string_free`.
+///
+/// `*out` is set to null on failure.
+///
+/// # Safety
+/// `config` must be null or point to a valid `ShuffleConfig` whose strings are null
+/// or nul-terminated. `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn shuffle_generate(
+    config: *const ShuffleConfig,
+    out: *mut *mut c_char,
+) -> ShuffleStatus {
+    if !out.is_null() {
+        *out = std::ptr::null_mut();
+    }
+    ffi_call(|| {
+        if out.is_null() {
+            return Err(null_pointer("out"));
+        }
+        let config = to_password_config(config)?;
+        let password = try_generate(&config)?;
+        *out = secret_to_c_string(password.expose_secret())?;
+        Ok(())
+    })
 }
 
+/// Generates `count` passwords across `threads` threads (all the cores when 0), all
+/// different when `unique` is set.
+///
+/// `*out` receives an array of `*out_count` strings, to be freed with
+/// `shuffle_batch_free`. It is set to null on failure.
+///
+/// # Safety
+/// `config` must be null or point to a valid `ShuffleConfig` whose strings are null
+/// or nul-terminated. `out` and `out_count` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn shuffle_generate_batch(
+    config: *const ShuffleConfig,
+    count: usize,
+    threads: usize,
+    unique: bool,
+    out: *mut *mut *mut c_char,
+    out_count: *mut usize,
+) -> ShuffleStatus {
+    if !out.is_null() {
+        *out = std::ptr::null_mut();
+    }
+    if !out_count.is_null() {
+        *out_count = 0;
+    }
+    ffi_call(|| {
+        if out.is_null() {
+            return Err(null_pointer("out"));
+        }
+        if out_count.is_null() {
+            return Err(null_pointer("out_count"));
+        }
+        let config = to_password_config(config)?;
+        let options = BatchOptions::default()
+            .with_threads(threads)
+            .with_unique(unique);
+        let passwords = generate_batch(&config, count, options)?;
 
-fn rust_to_c_string(s: String) -> *mut c_char {
-    CString::new(s).unwrap().into_raw()
+        let mut strings = Vec::with_capacity(passwords.len());
+        for password in &passwords {
+            match secret_to_c_string(password.expose_secret()) {
+                Ok(string) => strings.push(string),
+                Err(e) => {
+                    strings.into_iter().for_each(|string| shuffle_string_free(string));
+                    return Err(e);
+                }
+            }
+        }
+        *out_count = strings.len();
+        *out = Box::into_raw(strings.into_boxed_slice()) as *mut *mut c_char;
+        Ok(())
+    })
 }
 
-fn c_to_rust_string(ptr: *mut c_char) -> Result<String, String> {
-    let c_str: &CStr = unsafe { CStr::from_ptr(ptr) };
-    let res = c_str
+/// Wipes and frees the passwords of `shuffle_generate_batch` and their array. Does
+/// nothing on null.
+///
+/// # Safety
+/// `passwords` must be null or an array returned by `shuffle_generate_batch`, not
+/// freed yet, and `count` its number of passwords.
+#[no_mangle]
+pub unsafe extern "C" fn shuffle_batch_free(passwords: *mut *mut c_char, count: usize) {
+    if passwords.is_null() {
+        return;
+    }
+    let passwords = Box::from_raw(std::ptr::slice_from_raw_parts_mut(passwords, count));
+    passwords.iter().for_each(|password| shuffle_string_free(*password));
+}
+
+/// Validates a configuration.
+///
+/// Returns `SHUFFLE_STATUS_INVALID_CONFIG` when it is invalid: `*error` (unless
+/// null) receives the reason and `shuffle_last_error_message` describes it.
+/// `*error` is `SHUFFLE_CONFIG_ERROR_NONE` when the configuration is valid or
+/// could not be read.
+///
+/// # Safety
+/// `config` must be null or point to a valid `ShuffleConfig` whose strings are null
+/// or nul-terminated. `error` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn shuffle_config_validate(
+    config: *const ShuffleConfig,
+    error: *mut ShuffleConfigError,
+) -> ShuffleStatus {
+    if !error.is_null() {
+        *error = ShuffleConfigError::None;
+    }
+    ffi_call(|| {
+        let result = to_password_config(config).and_then(|config| Ok(config.validate()?));
+        if let (Err(e), false) = (&result, error.is_null()) {
+            *error = e.config_error;
+        }
+        result
+    })
+}
+
+/// Computes into `*out` the entropy in bits of a password generated with a valid
+/// configuration, see `PasswordConfig::entropy_bits`.
+///
+/// # Safety
+/// `config` must be null or point to a valid `ShuffleConfig` whose strings are null
+/// or nul-terminated. `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn shuffle_config_entropy_bits(
+    config: *const ShuffleConfig,
+    out: *mut f64,
+) -> ShuffleStatus {
+    if !out.is_null() {
+        *out = 0.0;
+    }
+    ffi_call(|| {
+        if out.is_null() {
+            return Err(null_pointer("out"));
+        }
+        let config = to_password_config(config)?;
+        config.validate()?;
+        *out = config.entropy_bits();
+        Ok(())
+    })
+}
+
+/// Wipes and frees a string returned by the library. Does nothing on null.
+///
+/// # Safety
+/// `ptr` must be null or a string returned by the library and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn shuffle_string_free(ptr: *mut c_char) {
+    if ptr.is_null() {
+        return;
+    }
+    let mut bytes = CString::from_raw(ptr).into_bytes();
+    bytes.zeroize();
+}
+
+fn null_pointer(name: &str) -> FfiError {
+    FfiError::new(
+        ShuffleStatus::NullPointer,
+        format!("`{}` must not be null", name),
+    )
+}
+
+/// Converts a C configuration, null strings being empty
+unsafe fn to_password_config(config: *const ShuffleConfig) -> Result<PasswordConfig, FfiError> {
+    let config = config.as_ref().ok_or_else(|| null_pointer("config"))?;
+    let optional = |ptr: *const c_char| {
+        if ptr.is_null() {
+            Ok(String::new())
+        } else {
+            c_to_rust_string(ptr)
+        }
+    };
+
+    let mut password_config = PasswordConfig::new(config.length)?
+        .excluded(optional(config.excluded)?)
+        .included(optional(config.included)?);
+    for (name, include) in [
+        (charset::UPPERCASE, config.uppercase),
+        (charset::LOWERCASE, config.lowercase),
+        (charset::DIGITS, config.digits),
+        (charset::BRACES, config.braces),
+        (charset::PUNCTUATION, config.punctuation),
+        (charset::QUOTES, config.quotes),
+        (charset::DASHES, config.dashes),
+        (charset::MATH, config.math),
+        (charset::LOGOGRAMS, config.logograms),
+    ] {
+        password_config = password_config.with_charset(name, include);
+    }
+    Ok(password_config)
+}
+
+/// Copies a password into a string owned by the caller
+fn secret_to_c_string(secret: &str) -> Result<*mut c_char, FfiError> {
+    CString::new(secret)
+        .map(CString::into_raw)
+        .map_err(|_| FfiError::new(ShuffleStatus::Internal, "password contains a nul byte"))
+}
+
+unsafe fn c_to_rust_string(ptr: *const c_char) -> Result<String, FfiError> {
+    if ptr.is_null() {
+        return Err(null_pointer("string"));
+    }
+    CStr::from_ptr(ptr)
         .to_str()
-        .map_err(|_| "Could not convert C string to Rust string".to_string())?
-        .to_string();
-    Ok(res)
+        .map(str::to_string)
+        .map_err(|_| FfiError::new(ShuffleStatus::InvalidUtf8, "string is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(shuffle_last_error_message()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_api_version_is_soname_version() {
+        assert_eq!(
+            SHUFFLE_API_VERSION.to_string(),
+            env!("CARGO_PKG_VERSION_MAJOR")
+        );
+        assert!(env!("SHUFFLE_SONAME").contains(&SHUFFLE_API_VERSION.to_string()));
+    }
+
+    #[test]
+    fn test_generate() {
+        let excluded = CString::new("abc").unwrap();
+        let config = ShuffleConfig {
+            length: 300,
+            uppercase: false,
+            excluded: excluded.as_ptr(),
+            ..shuffle_config_default()
+        };
+        let mut out = std::ptr::null_mut();
+
+        assert_eq!(unsafe { shuffle_generate(&config, &mut out) }, ShuffleStatus::Ok);
+        let password = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_string();
+        unsafe { shuffle_string_free(out) };
+
+        assert_eq!(password.len(), 300);
+        assert!(!password.contains(|c: char| "abcABC".contains(c)));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = ShuffleConfig {
+            lowercase: false,
+            uppercase: false,
+            digits: false,
+            ..shuffle_config_default()
+        };
+        let mut out = std::ptr::null_mut();
+
+        assert_eq!(
+            unsafe { shuffle_generate(&config, &mut out) },
+            ShuffleStatus::InvalidConfig
+        );
+        assert!(out.is_null());
+        assert_eq!(last_error(), "At least one character set must be included.");
+    }
+
+    #[test]
+    fn test_generate_batch() {
+        let config = ShuffleConfig {
+            length: 3,
+            lowercase: false,
+            uppercase: false,
+            ..shuffle_config_default()
+        };
+        let mut out = std::ptr::null_mut();
+        let mut count = 0;
+
+        let status = unsafe { shuffle_generate_batch(&config, 1000, 2, true, &mut out, &mut count) };
+        assert_eq!(status, ShuffleStatus::Ok);
+        let passwords: std::collections::HashSet<String> = (0..count)
+            .map(|i| unsafe { CStr::from_ptr(*out.add(i)) }.to_string_lossy().into_owned())
+            .collect();
+        unsafe { shuffle_batch_free(out, count) };
+        assert_eq!(passwords.len(), 1000);
+
+        let status = unsafe { shuffle_generate_batch(&config, 1001, 0, true, &mut out, &mut count) };
+        assert_eq!(status, ShuffleStatus::ConstraintsUnsatisfiable);
+        assert!(out.is_null());
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_config_validate() {
+        let mut config = shuffle_config_default();
+        let mut error = ShuffleConfigError::ZeroLength;
+
+        assert_eq!(unsafe { shuffle_config_validate(&config, &mut error) }, ShuffleStatus::Ok);
+        assert_eq!(error, ShuffleConfigError::None);
+
+        config.length = 0;
+        assert_eq!(
+            unsafe { shuffle_config_validate(&config, &mut error) },
+            ShuffleStatus::InvalidConfig
+        );
+        assert_eq!(error, ShuffleConfigError::ZeroLength);
+
+        let excluded = CString::new("0123456789").unwrap();
+        config = ShuffleConfig {
+            excluded: excluded.as_ptr(),
+            ..shuffle_config_default()
+        };
+        assert_eq!(
+            unsafe { shuffle_config_validate(&config, &mut error) },
+            ShuffleStatus::InvalidConfig
+        );
+        assert_eq!(error, ShuffleConfigError::EmptyCharacterSet);
+        assert_eq!(last_error(), "No 'digits' character is left after exclusions.");
+    }
+
+    #[test]
+    fn test_config_entropy_bits() {
+        let config = ShuffleConfig {
+            length: 10,
+            lowercase: false,
+            uppercase: false,
+            ..shuffle_config_default()
+        };
+        let mut bits = 0.0;
+
+        assert_eq!(
+            unsafe { shuffle_config_entropy_bits(&config, &mut bits) },
+            ShuffleStatus::Ok
+        );
+        assert!((bits - 10.0 * 10f64.log2()).abs() < 1e-9);
+        assert_eq!(
+            unsafe { shuffle_config_entropy_bits(&config, std::ptr::null_mut()) },
+            ShuffleStatus::NullPointer
+        );
+    }
+
+    #[test]
+    fn test_null_pointers() {
+        let config = shuffle_config_default();
+        let mut out = std::ptr::null_mut();
+        let invalid = [0xffu8, 0];
+        let invalid_config = ShuffleConfig {
+            included: invalid.as_ptr() as *const c_char,
+            ..config
+        };
+
+        assert_eq!(
+            unsafe { shuffle_generate(std::ptr::null(), &mut out) },
+            ShuffleStatus::NullPointer
+        );
+        assert_eq!(
+            unsafe { shuffle_generate(&config, std::ptr::null_mut()) },
+            ShuffleStatus::NullPointer
+        );
+        assert_eq!(
+            unsafe { shuffle_generate(&invalid_config, &mut out) },
+            ShuffleStatus::InvalidUtf8
+        );
+        unsafe { shuffle_string_free(std::ptr::null_mut()) };
+    }
 }
@@ -0,0 +1,94 @@
+#[cfg(all(test, unix))]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    /// Directory of the libraries built for the tests, where the test binary is
+    fn deps_dir() -> PathBuf {
+        std::env::current_exe().unwrap().parent().unwrap().to_path_buf()
+    }
+
+    fn out_dir() -> PathBuf {
+        let out_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("c_smoke");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        out_dir
+    }
+
+    /// Compiles `tests/<source>` into `program` with the headers generated by the build
+    fn compile(source: &str, cpp: bool, program: &Path, link_args: &[String]) {
+        let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let mut build = cc::Build::new();
+        build
+            .cargo_metadata(false)
+            .cpp(cpp)
+            .target(env!("SHUFFLE_TARGET"))
+            .host(env!("SHUFFLE_TARGET"))
+            .opt_level(0);
+        if cpp {
+            build.std("c++14");
+        }
+        let status = build
+            .get_compiler()
+            .to_command()
+            .arg(crate_dir.join("tests").join(source))
+            .arg("-I")
+            .arg(Path::new(env!("OUT_DIR")).join("include"))
+            .args(link_args)
+            .arg("-o")
+            .arg(program)
+            .status()
+            .unwrap();
+        assert!(status.success(), "could not compile tests/{}", source);
+    }
+
+    fn run(program: &Path) {
+        let output = Command::new(program).output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+    }
+
+    #[test]
+    fn test_c_smoke() {
+        let out_dir = out_dir();
+
+        // the program asks the loader for the SONAME, point it to the built library
+        let library = deps_dir().join(format!("libcpp_adapter{}", std::env::consts::DLL_SUFFIX));
+        let soname = out_dir.join(env!("SHUFFLE_SONAME"));
+        let _ = std::fs::remove_file(&soname);
+        std::os::unix::fs::symlink(&library, &soname).unwrap();
+
+        let program = out_dir.join("smoke");
+        compile(
+            "smoke.c",
+            false,
+            &program,
+            &[
+                format!("-L{}", deps_dir().display()),
+                "-lcpp_adapter".to_string(),
+                format!("-Wl,-rpath,{}", out_dir.display()),
+            ],
+        );
+        run(&program);
+    }
+
+    #[test]
+    fn test_cxx_smoke() {
+        let program = out_dir().join("cxx_smoke");
+        compile(
+            "cxx_smoke.cpp",
+            true,
+            &program,
+            &[
+                deps_dir().join("libcpp_adapter.a").display().to_string(),
+                "-lpthread".to_string(),
+                "-ldl".to_string(),
+                "-lm".to_string(),
+            ],
+        );
+        run(&program);
+    }
+}
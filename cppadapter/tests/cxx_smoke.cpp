@@ -0,0 +1,49 @@
+// Smoke test of the cxx bridge in the static library, built and run by tests/c_smoke.rs
+#include <cstdio>
+#include <string>
+
+#include "rust/cxx.h"
+#include "shuffle_cxx.h"
+
+#define CHECK(cond)                                                          \
+    do {                                                                     \
+        if (!(cond)) {                                                       \
+            std::fprintf(stderr, "%s:%d: %s failed\n", __FILE__, __LINE__,   \
+                         #cond);                                             \
+            return 1;                                                        \
+        }                                                                    \
+    } while (0)
+
+int main() {
+    rust::Box<shuffle::PasswordConfig> config = shuffle::new_password_config(32);
+    config->set_math(true);
+    config->set_excluded("0O1Il5S");
+    config->validate();
+    CHECK(config->entropy_bits() > 150);
+
+    std::string password(shuffle::generate(*config));
+    CHECK(password.find_first_of("0O1Il5S") == std::string::npos);
+
+    config->set_uppercase(false);
+    config->set_lowercase(false);
+    config->set_digits(false);
+    config->set_math(false);
+    bool thrown = false;
+    try {
+        shuffle::generate(*config);
+    } catch (const rust::Error &e) {
+        thrown = std::string(e.what()) == "At least one character set must be included.";
+    }
+    CHECK(thrown);
+
+    thrown = false;
+    try {
+        shuffle::new_password_config(0);
+    } catch (const rust::Error &) {
+        thrown = true;
+    }
+    CHECK(thrown);
+
+    std::puts("ok");
+    return 0;
+}
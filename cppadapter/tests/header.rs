@@ -0,0 +1,18 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn test_committed_header_is_up_to_date() {
+        let committed =
+            std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("include/shuffle.h"))
+                .unwrap()
+                .replace("\r\n", "\n");
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/include/shuffle.h"));
+
+        assert!(
+            committed == generated,
+            "include/shuffle.h is stale, run `cargo xtask header`"
+        );
+    }
+}
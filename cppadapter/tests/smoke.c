@@ -0,0 +1,58 @@
+/* Smoke test of the shared library, built and run by tests/c_smoke.rs */
+#include <stdio.h>
+#include <string.h>
+
+#include "shuffle.h"
+
+#define CHECK(cond)                                                  \
+    do {                                                             \
+        if (!(cond)) {                                               \
+            fprintf(stderr, "%s:%d: %s failed\n", __FILE__, __LINE__, \
+                    #cond);                                          \
+            return 1;                                                \
+        }                                                            \
+    } while (0)
+
+int main(void) {
+    CHECK(shuffle_api_version() == SHUFFLE_API_VERSION);
+    CHECK(strlen(shuffle_version()) > 0);
+
+    ShuffleConfig config = shuffle_config_default();
+    config.length = 32;
+    config.excluded = "0O1Il5S";
+    char *password = NULL;
+    CHECK(shuffle_generate(&config, &password) == SHUFFLE_STATUS_OK);
+    CHECK(password != NULL);
+    CHECK(strlen(password) == 32);
+    CHECK(strpbrk(password, "0O1Il5S") == NULL);
+    shuffle_string_free(password);
+
+    double bits = 0;
+    CHECK(shuffle_config_entropy_bits(&config, &bits) == SHUFFLE_STATUS_OK);
+    CHECK(bits > 150 && bits < 200);
+
+    char **passwords = NULL;
+    size_t count = 0;
+    CHECK(shuffle_generate_batch(&config, 100, 0, true, &passwords, &count) == SHUFFLE_STATUS_OK);
+    CHECK(count == 100);
+    for (size_t i = 0; i < count; i++) {
+        CHECK(strlen(passwords[i]) == 32);
+    }
+    shuffle_batch_free(passwords, count);
+
+    config.lowercase = false;
+    config.uppercase = false;
+    config.digits = false;
+    ShuffleConfigError error = SHUFFLE_CONFIG_ERROR_NONE;
+    CHECK(shuffle_config_validate(&config, &error) == SHUFFLE_STATUS_INVALID_CONFIG);
+    CHECK(error == SHUFFLE_CONFIG_ERROR_NO_CHARACTER_SETS_ENABLED);
+    CHECK(shuffle_generate(&config, &password) == SHUFFLE_STATUS_INVALID_CONFIG);
+    CHECK(password == NULL);
+    CHECK(shuffle_last_error_message() != NULL);
+
+    CHECK(shuffle_generate(NULL, &password) == SHUFFLE_STATUS_NULL_POINTER);
+    shuffle_string_free(NULL);
+
+    puts("ok");
+    return 0;
+}
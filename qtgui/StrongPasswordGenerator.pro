@@ -8,9 +8,13 @@ CONFIG += c++17
 # In order to do so, uncomment the following line.
 #DEFINES += QT_DISABLE_DEPRECATED_BEFORE=0x060000    # disables all the APIs deprecated before Qt 6.0.0
 
-unix: LIBS += -L$$PWD/../target/release/ -lcpp_adapter
-unix: PRE_TARGETDEPS += $$PWD/../target/release/libcpp_adapter.a
-unix: LIBS += -ldl
+# the C++ bridge is only in the static library: link the .a itself, -lcpp_adapter
+# would pick the shared library
+DEFINES += SHUFFLE_CXX_BRIDGE
+unix: LIBS += $$PWD/../target/shuffle/lib/libcpp_adapter.a
+unix: PRE_TARGETDEPS += $$PWD/../target/shuffle/lib/libcpp_adapter.a
+unix: LIBS += -lpthread -ldl -lm
+INCLUDEPATH += $$PWD/../target/shuffle/include
 
 SOURCES += \
     main.cpp \
@@ -1,25 +1,13 @@
 #ifndef ADAPTER_H
 #define ADAPTER_H
 
-#include <QFileDialog>
-#include <QString>
-
-// rust functions
-extern "C" char* get_version();
-
-extern "C" char* get_random(int length,
-                            bool uppercase,
-                            bool lowercase,
-                            bool numbers,
-                            bool braces,
-                            bool punctuation,
-                            bool quotes,
-                            bool dashes,
-                            bool math,
-                            bool logograms,
-                            const char* avoid,
-                            const char* also);
-
-
+#ifdef SHUFFLE_CXX_BRIDGE
+// C++ API of the rust library, generated by cxx, only in the static library
+#include "rust/cxx.h"
+#include "shuffle_cxx.h"
+#else
+// C API of the installed shared library
+#include "shuffle.h"
+#endif
 
 #endif // ADAPTER_H
@@ -108,21 +108,55 @@ void MainWindow::onGenerateClick()
         ui->btn_copy->setDisabled(false);
 
 
-        auto test = get_random(pwdLen,
-                               upperCase,
-                               lowerCase,
-                               numbers,
-                               braces,
-                               punctuation,
-                               quotes,
-                               dashes,
-                               math,
-                               logograms,
-                               avoid,
-                               also);
-
         ui->passwordEdit->clear();
-        ui->passwordEdit->setText(test);
+#ifdef SHUFFLE_CXX_BRIDGE
+        try {
+            rust::Box<shuffle::PasswordConfig> config = shuffle::new_password_config(pwdLen);
+            config->set_uppercase(upperCase);
+            config->set_lowercase(lowerCase);
+            config->set_digits(numbers);
+            config->set_braces(braces);
+            config->set_punctuation(punctuation);
+            config->set_quotes(quotes);
+            config->set_dashes(dashes);
+            config->set_math(math);
+            config->set_logograms(logograms);
+            config->set_excluded(avoid);
+            config->set_included(also);
+
+            rust::String password = shuffle::generate(*config);
+            ui->passwordEdit->setText(QString::fromUtf8(password.data(), password.size()));
+            ui->statusbar->showMessage(QString("Entropy: %1 bits").arg(config->entropy_bits(), 0, 'f', 1));
+        } catch (const rust::Error &e) {
+            ui->statusbar->showMessage(e.what());
+        }
+#else
+        ShuffleConfig config = shuffle_config_default();
+        config.length = pwdLen;
+        config.uppercase = upperCase;
+        config.lowercase = lowerCase;
+        config.digits = numbers;
+        config.braces = braces;
+        config.punctuation = punctuation;
+        config.quotes = quotes;
+        config.dashes = dashes;
+        config.math = math;
+        config.logograms = logograms;
+        config.excluded = avoid;
+        config.included = also;
+
+        char* password = nullptr;
+        double bits = 0;
+        if (shuffle_generate(&config, &password) == SHUFFLE_STATUS_OK) {
+            ui->passwordEdit->setText(password);
+            shuffle_string_free(password);
+            if (shuffle_config_entropy_bits(&config, &bits) == SHUFFLE_STATUS_OK) {
+                ui->statusbar->showMessage(QString("Entropy: %1 bits").arg(bits, 0, 'f', 1));
+            }
+        } else {
+            ui->statusbar->showMessage(shuffle_last_error_message());
+        }
+#endif
     }
 }
 
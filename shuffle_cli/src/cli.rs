@@ -1,15 +1,93 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use shuffle_core::{charset, Capitalization, CharsetRegistry};
 
 #[derive(Parser, Debug)]
 #[clap(
     author,
     about,
     version,
-    after_help = "If you do not specify any of the \
-[--uppercase, --lowercase, --digits, --symbols] flags, \
+    args_conflicts_with_subcommands = true,
+    after_help = "Without a command, generate a password (same as `shuffle generate`).\n\n\
+If you do not specify any of the [--uppercase, --lowercase, --digits, --symbols] flags, \
 then uppercase lowercase and digits will be used."
 )]
 pub struct Cli {
+    #[clap(flatten)]
+    pub(crate) generate: GenerateArgs,
+
+    #[clap(subcommand)]
+    pub(crate) command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate a password (the default command)
+    Generate(GenerateArgs),
+
+    /// Generate a passphrase of random words
+    Passphrase {
+        /// Sets the number of words
+        #[clap(short, long, value_name = "NUMBER", default_value = "6")]
+        words: usize,
+
+        /// Separator between words
+        #[clap(long, default_value = "-")]
+        separator: String,
+
+        /// Capitalization of the words
+        #[clap(long, value_enum, default_value = "lower")]
+        capitalization: CapitalizationArg,
+
+        /// Append a random digit to one of the words
+        #[clap(short, long)]
+        digit: bool,
+
+        /// Append a random symbol to one of the words
+        #[clap(short, long)]
+        symbol: bool,
+
+        /// Use the words of a file, one per line, instead of the built-in wordlist
+        #[clap(long, value_name = "FILE")]
+        wordlist: Option<String>,
+    },
+
+    /// Generate a numeric PIN
+    Pin {
+        /// Sets the required PIN length
+        #[clap(short = 'L', long, value_name = "NUMBER", default_value = "4")]
+        length: usize,
+
+        /// Allow the most common PINs (1234, 0000, 1212...)
+        #[clap(long)]
+        no_blocklist: bool,
+    },
+
+    /// Estimate the strength of a password read from stdin
+    Check,
+
+    /// Print the entropy in bits of the passwords generated with the given flags
+    Entropy(PasswordArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct GenerateArgs {
+    #[clap(flatten)]
+    pub(crate) password: PasswordArgs,
+
+    /// Output in a txt file
+    #[clap(long)]
+    output: Option<String>,
+}
+
+impl GenerateArgs {
+    pub fn output(&self) -> Option<String> {
+        self.output.clone()
+    }
+}
+
+/// Flags describing the generated passwords
+#[derive(Args, Debug)]
+pub struct PasswordArgs {
     /// Use UPPERCASE letters [A-Z]
     #[clap(short, long)]
     pub(crate) uppercase: bool,
@@ -22,22 +100,31 @@ pub struct Cli {
     #[clap(short, long)]
     pub(crate) digits: bool,
 
-    /// Use special symbols [*&^%$#@!~]
+    /// Use special symbols, all of them counted as a single group
+    #[clap(short, long)]
+    pub(crate) symbols: bool,
+
+    /// Use braces [(){}[]]
     #[clap(short, long)]
     pub(crate) braces: bool,
 
+    /// Use punctuation [.,:;]
     #[clap(short, long)]
     pub(crate) punctuation: bool,
 
+    /// Use quotes ["']
     #[clap(short, long)]
     pub(crate) quotes: bool,
 
+    /// Use dashes [-/\_|]
     #[clap(long)]
     pub(crate) dashes: bool,
 
+    /// Use math symbols [!*+<=>?]
     #[clap(short, long)]
     pub(crate) math: bool,
 
+    /// Use logograms [#$%&@^`~]
     #[clap(long)]
     pub(crate) logograms: bool,
 
@@ -45,10 +132,6 @@ pub struct Cli {
     #[clap(short = 'L', long, value_name = "NUMBER", default_value = "20")]
     length: usize,
 
-    /// Output in a txt file
-    #[clap(long)]
-    output: Option<String>,
-
     /// Exclude char
     #[clap(long)]
     exclude: Option<String>,
@@ -57,18 +140,16 @@ pub struct Cli {
     #[clap(long)]
     include: Option<String>,
 
+    /// Use a custom group of chars, e.g. --charset base58=123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz
+    #[clap(long, value_name = "NAME=CHARS", value_parser = parse_charset)]
+    charset: Vec<(String, String)>,
 }
 
-impl Cli {
-
+impl PasswordArgs {
     pub fn length(&self) -> usize {
         self.length
     }
 
-    pub fn output(&self) -> Option<String> {
-        self.output.clone()
-    }
-
     pub fn exclude(&self) -> Option<String> {
         self.exclude.clone()
     }
@@ -76,5 +157,64 @@ impl Cli {
     pub fn include(&self) -> Option<String> {
         self.include.clone()
     }
+
+    /// Built-in groups of chars selected by the flags
+    pub fn charsets(&self) -> [(&'static str, bool); 9] {
+        [
+            (charset::LOWERCASE, self.lowercase),
+            (charset::UPPERCASE, self.uppercase),
+            (charset::DIGITS, self.digits),
+            (charset::BRACES, self.braces),
+            (charset::PUNCTUATION, self.punctuation),
+            (charset::QUOTES, self.quotes),
+            (charset::DASHES, self.dashes),
+            (charset::MATH, self.math),
+            (charset::LOGOGRAMS, self.logograms),
+        ]
+    }
+
+    /// Custom groups of chars given with --charset, and the `symbols` group of
+    /// --symbols
+    pub fn custom_charsets(&self) -> Vec<(String, String)> {
+        let mut charsets = self.charset.clone();
+        if self.symbols {
+            let registry = CharsetRegistry::default();
+            let symbols: String = charset::SYMBOL_CHARSETS
+                .iter()
+                .filter_map(|name| registry.get(name))
+                .map(|set| set.chars.as_str())
+                .collect();
+            charsets.push(("symbols".to_string(), symbols));
+        }
+        charsets
+    }
 }
 
+/// Capitalization of the passphrase words
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum CapitalizationArg {
+    Lower,
+    Upper,
+    Title,
+    Random,
+}
+
+impl From<CapitalizationArg> for Capitalization {
+    fn from(arg: CapitalizationArg) -> Self {
+        match arg {
+            CapitalizationArg::Lower => Self::Lower,
+            CapitalizationArg::Upper => Self::Upper,
+            CapitalizationArg::Title => Self::Title,
+            CapitalizationArg::Random => Self::Random,
+        }
+    }
+}
+
+fn parse_charset(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, chars)) if !name.is_empty() && !chars.is_empty() => {
+            Ok((name.to_string(), chars.to_string()))
+        }
+        _ => Err("expected NAME=CHARS".to_string()),
+    }
+}
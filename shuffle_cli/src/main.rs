@@ -1,11 +1,12 @@
 extern crate core;
 
-use crate::cli::Cli;
+use crate::cli::{Cli, Command, GenerateArgs, PasswordArgs};
 
 use clap::Parser;
 use shuffle_core::*;
 use std::{fs::File, io::LineWriter, io::Write};
 use color_eyre::eyre::{eyre, Result};
+use zeroize::Zeroizing;
 
 use question::{Answer, Question};
 
@@ -13,7 +14,7 @@ pub mod cli;
 
 fn main() {
     if let Err(e) = run() {
-        eprintln!("{} {}", "Error :", e);
+        eprintln!("Error : {}", e);
         std::process::exit(1);
     }
 }
@@ -25,44 +26,79 @@ fn run() -> Result<()> {
 
     let opts: Cli = Cli::parse();
 
-    let total_true = [
-        opts.lowercase,
-        opts.uppercase,
-        opts.digits,
-        opts.braces,
-        opts.punctuation,
-        opts.quotes,
-        opts.dashes,
-        opts.math,
-        opts.logograms
-    ].into_iter().filter(|b| *b).count();
-
-    if opts.length()<total_true || opts.length()==0{
-        return Err(eyre!("Password length must be greater or equal to the number of selected group chars."));
+    match opts.command {
+        None => generate(opts.generate),
+        Some(Command::Generate(args)) => generate(args),
+        Some(Command::Passphrase {
+            words,
+            separator,
+            capitalization,
+            digit,
+            symbol,
+            wordlist,
+        }) => {
+            let mut config = PassphraseConfig::new(words)?
+                .with_separator(separator)
+                .with_capitalization(capitalization.into())
+                .with_digit(digit)
+                .with_symbol(symbol);
+            if let Some(path) = wordlist {
+                let content = std::fs::read_to_string(&path)
+                    .map_err(|e| eyre!("Can't read wordlist '{}': {}", path, e))?;
+                let words = content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|word| !word.is_empty())
+                    .map(String::from)
+                    .collect();
+                config = config.with_wordlist(words);
+            }
+            println!("{}", generate_passphrase(&config)?.expose_secret());
+            Ok(())
+        }
+        Some(Command::Pin { length, no_blocklist }) => {
+            let config = PinConfig::new(length)?.with_blocklist(!no_blocklist);
+            println!("{}", generate_pin(&config)?.expose_secret());
+            Ok(())
+        }
+        Some(Command::Check) => check(),
+        Some(Command::Entropy(args)) => {
+            let config = password_config(&args)?;
+            println!("{:.2}", config.entropy_bits());
+            Ok(())
+        }
     }
+}
+
+/// Builds and validates the configuration described by the password flags
+fn password_config(args: &PasswordArgs) -> Result<PasswordConfig> {
+    let mut config = PasswordConfig::new(args.length())?
+        .excluded(args.exclude().unwrap_or("".to_string()))
+        .included(args.include().unwrap_or("".to_string()));
 
-    let config = PasswordConfig::new(opts.length())?
-        .with_lowercase(opts.lowercase)
-        .with_uppercase(opts.uppercase)
-        .with_digits(opts.digits)
-        .with_braces(opts.braces)
-        .with_punctuation(opts.punctuation)
-        .with_quotes(opts.quotes)
-        .with_dashes(opts.dashes)
-        .with_math(opts.math)
-        .with_logograms(opts.logograms)
-        .excluded(opts.exclude().unwrap_or("".to_string()))
-        .included(opts.include().unwrap_or("".to_string()));
-
-    if !config.validate().is_ok(){
-        return Err(eyre!("Invalid configuration. Please enter some options to generate a password"));
+    for (name, include) in args.charsets() {
+        config = config.with_charset(name, include);
+    }
+    for (name, chars) in args.custom_charsets() {
+        config = config.with_custom_charset(&name, &chars);
     }
 
-    let password = generate_password(&config);
-    println!("{}", password);
+    let total_true = config.charsets.enabled().count();
 
-    if opts.output().is_some() {
-        let dest = opts.output().unwrap();
+    if args.length()<total_true {
+        return Err(eyre!("Password length must be greater or equal to the number of selected group chars."));
+    }
+    config.validate()?;
+    Ok(config)
+}
+
+fn generate(args: GenerateArgs) -> Result<()> {
+    let config = password_config(&args.password)?;
+    let password = try_generate(&config)?;
+    println!("{}", password.expose_secret());
+
+    if args.output().is_some() {
+        let dest = args.output().unwrap();
         if std::path::Path::new(&dest).is_dir() && &dest != "/dev/null" {
             return Err(eyre!("Can't save file. A folder with this name exist."));
         }
@@ -74,31 +110,36 @@ fn run() -> Result<()> {
                 .confirm();
 
             if answer == Answer::YES {
-                writetxt(password.clone(), &dest).map_err(|e| eyre!(e))?;
-                println!(
-                    "{} '{}' {}",
-                    "File",
-                    opts.output().unwrap(),
-                    "was overwritten."
-                );
+                writetxt(&password, &dest).map_err(|e| eyre!(e))?;
+                println!("File '{}' was overwritten.", dest);
             } else {
-                println!("{}", "Writting file canceled.");
+                println!("Writting file canceled.");
             }
         } else {
-            writetxt(password.clone(), &dest)?;
+            writetxt(&password, &dest)?;
         }
     }
     Ok(())
 }
 
-fn writetxt(x: String, dest: &String) -> Result<()> {
+/// Rates the password read from stdin, without the line ending
+fn check() -> Result<()> {
+    let mut input = Zeroizing::new(String::new());
+    std::io::stdin().read_line(&mut input)?;
+    let password = input.trim_end_matches(['\n', '\r']);
+
+    let bits = estimate_entropy_bits(password);
+    println!("{:.2} bits ({})", bits, Strength::from_bits(bits));
+    Ok(())
+}
+
+fn writetxt(x: &SecretString, dest: &String) -> Result<()> {
     let file = File::create(dest)?;
     let mut file = LineWriter::new(file);
 
-    let z=x.to_string();
-    file.write_all(z.as_bytes())?;
+    file.write_all(x.expose_secret().as_bytes())?;
 
-    println!("{}", "File Saved.");
+    println!("File Saved.");
 
     Ok(())
 }
@@ -23,7 +23,7 @@ mod tests {
 
         for _ in 0..100 {
             let mut cmd = cmd();
-            cmd.args(&["-L", "24","-dlu"])
+            cmd.args(["-L", "24","-dlu"])
                 .assert()
                 .stdout(predicate::str::is_match(pattern).unwrap());
         }
@@ -35,7 +35,7 @@ mod tests {
 
         for _ in 0..100 {
             let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
-            cmd.args(&["-L", "100","-dlu"])
+            cmd.args(["-L", "100","-dlu"])
                 .assert()
                 .stdout(predicate::str::is_match(pattern).unwrap());
         }
@@ -65,6 +65,92 @@ mod tests {
     }
 
 
+    #[test]
+    fn test_create_custom_charset() {
+        let pattern = r"^[a-f0-9]{32}\n$"; // Ensure exactly 32 hex chars + newline
+
+        for _ in 0..100 {
+            let mut cmd = cmd();
+            cmd.args(["-L", "32", "--charset", "hex=0123456789abcdef"])
+                .assert()
+                .stdout(predicate::str::is_match(pattern).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_create_pin() {
+        let pattern = r"^[0-9]{6}\n$"; // Ensure exactly 6 digits + newline
+
+        for _ in 0..100 {
+            let mut cmd = cmd();
+            cmd.args(["pin", "-L", "6"])
+                .assert()
+                .stdout(predicate::str::is_match(pattern).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_pin_too_short() {
+        let mut cmd = cmd();
+        cmd.args(["pin", "-L", "3"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("PIN length must be at least 4"));
+    }
+
+    #[test]
+    fn test_symbols_and_default_command() {
+        // the flags of `generate` keep working without the subcommand
+        for args in [&["-ulds", "-L", "30"][..], &["generate", "-ulds", "-L", "30"]] {
+            let mut cmd = cmd();
+            cmd.args(args)
+                .assert()
+                .success()
+                .stdout(predicate::str::is_match(r"^\S{30}\n$").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_symbols_short_length() {
+        // --symbols is a single group, so 4 groups fit in 8 chars
+        for _ in 0..100 {
+            let mut cmd = cmd();
+            cmd.args(["-ulds", "-L", "8"])
+                .assert()
+                .success()
+                .stdout(predicate::str::is_match(r"^\S{8}\n$").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_passphrase() {
+        let mut cmd = cmd();
+        cmd.args(["passphrase", "-w", "4", "--separator", "_", "--capitalization", "upper"])
+            .assert()
+            .success()
+            .stdout(predicate::str::is_match(r"^[A-Z]+(_[A-Z]+){3}\n$").unwrap());
+    }
+
+    #[test]
+    fn test_check() {
+        let mut cmd = cmd();
+        cmd.arg("check")
+            .write_stdin("1234\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("(very weak)"));
+    }
+
+    #[test]
+    fn test_entropy() {
+        // 10 digits: 10 * log2(10)
+        let mut cmd = cmd();
+        cmd.args(["entropy", "-d", "-L", "10"])
+            .assert()
+            .success()
+            .stdout("33.22\n");
+    }
+
     #[test]
     fn integration_error() {
         // Check if an error occurs
@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use shuffle_core::{generate_password, PasswordConfig, PasswordGenerator};
+
+const BATCH: usize = 1_000;
+
+fn bench_generators(c: &mut Criterion) {
+    let config = PasswordConfig::new(20).unwrap().with_math(true).with_logograms(true);
+    let mut group = c.benchmark_group("1000 passwords of 20 chars");
+
+    group.bench_function("generate_password", |b| {
+        b.iter(|| {
+            for _ in 0..BATCH {
+                black_box(generate_password(&config).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("PasswordGenerator", |b| {
+        let mut generator = PasswordGenerator::new(&config).unwrap();
+        b.iter(|| {
+            for _ in 0..BATCH {
+                black_box(generator.next_password().unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_generators);
+criterion_main!(benches);
@@ -0,0 +1,217 @@
+use crate::config::PasswordConfig;
+use crate::generator::{GenerateError, PasswordGenerator, MAX_ATTEMPTS};
+use crate::secret::SecretString;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::sync_channel;
+
+/// Number of passwords a thread generates before handing them over
+const CHUNK_SIZE: usize = 256;
+
+/// Number of chunks waiting to be consumed before the threads block
+const CHANNEL_CHUNKS: usize = 4;
+
+/// Options for bulk generation
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// Number of generating threads, all the available cores when 0
+    pub threads: usize,
+
+    /// Never emit the same password twice in a batch
+    pub unique: bool,
+}
+
+impl BatchOptions {
+    /// Builder method to set the number of generating threads
+    pub const fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Builder method to set whether the passwords of a batch are all different
+    pub const fn with_unique(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        self
+    }
+
+    fn thread_count(&self) -> usize {
+        match self.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            threads => threads,
+        }
+    }
+}
+
+/// Generates `count` passwords across threads
+///
+/// # Examples
+/// ```
+/// # use shuffle_core::{generate_batch, BatchOptions, PasswordConfig};
+///
+///  let config = PasswordConfig::new(12).unwrap();
+///  let passwords = generate_batch(&config, 1000, BatchOptions::default().with_unique(true)).unwrap();
+/// assert_eq!(passwords.len(), 1000);
+/// ```
+pub fn generate_batch(
+    config: &PasswordConfig,
+    count: usize,
+    options: BatchOptions,
+) -> Result<Vec<SecretString>, GenerateError> {
+    let mut passwords = Vec::with_capacity(count);
+    run_batch(config, count, &options, |password| {
+        passwords.push(password);
+        Ok(())
+    })?;
+    Ok(passwords)
+}
+
+/// Generates `count` passwords across threads and writes them to `writer`, one per line.
+///
+/// Only the passwords waiting to be written are held in memory (plus 8 bytes per
+/// password when `options.unique` is set).
+pub fn generate_batch_to<W: Write>(
+    config: &PasswordConfig,
+    count: usize,
+    options: BatchOptions,
+    writer: W,
+) -> Result<(), GenerateError> {
+    let mut writer = BufWriter::new(writer);
+    run_batch(config, count, &options, |password| {
+        writeln!(writer, "{}", password.expose_secret()).map_err(GenerateError::Io)
+    })?;
+    writer.flush().map_err(GenerateError::Io)
+}
+
+/// Feeds `count` passwords to `sink`.
+///
+/// Each thread owns a `PasswordGenerator`, so every thread draws from its own RNG
+/// seeded from the OS. The calling thread collects the passwords, drops the
+/// duplicates when asked and tells the threads to stop once it has enough.
+fn run_batch<F>(
+    config: &PasswordConfig,
+    count: usize,
+    options: &BatchOptions,
+    mut sink: F,
+) -> Result<(), GenerateError>
+where
+    F: FnMut(SecretString) -> Result<(), GenerateError>,
+{
+    // fail before spawning anything on an invalid configuration
+    config.validate()?;
+    if count == 0 {
+        return Ok(());
+    }
+
+    let threads = options.thread_count();
+    let done = AtomicBool::new(false);
+    let (sender, receiver) = sync_channel(CHANNEL_CHUNKS * threads);
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            let sender = sender.clone();
+            let done = &done;
+            scope.spawn(move || {
+                let mut generator = match PasswordGenerator::new(config) {
+                    Ok(generator) => generator,
+                    Err(e) => {
+                        let _ = sender.send(Err(e));
+                        return;
+                    }
+                };
+                while !done.load(Ordering::Relaxed) {
+                    let chunk: Result<Vec<SecretString>, GenerateError> =
+                        (0..CHUNK_SIZE).map(|_| generator.next_password()).collect();
+                    let failed = chunk.is_err();
+                    if sender.send(chunk).is_err() || failed {
+                        return;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        // A password is identified by a keyed 64-bit hash. A collision only drops a
+        // password which was in fact new, so the batch stays free of duplicates.
+        let hasher = RandomState::new();
+        let mut seen = HashSet::new();
+        let mut emitted = 0;
+        let mut duplicates = 0;
+
+        let result = (|| {
+            for chunk in &receiver {
+                for password in chunk? {
+                    if options.unique && !seen.insert(hasher.hash_one(password.expose_secret())) {
+                        duplicates += 1;
+                        if duplicates == MAX_ATTEMPTS {
+                            return Err(GenerateError::NotEnoughUniquePasswords {
+                                requested: count,
+                                unique: emitted,
+                            });
+                        }
+                        continue;
+                    }
+                    duplicates = 0;
+                    sink(password)?;
+                    emitted += 1;
+                    if emitted == count {
+                        return Ok(());
+                    }
+                }
+            }
+            Ok(())
+        })();
+
+        done.store(true, Ordering::Relaxed);
+        drop(receiver);
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(length: usize) -> PasswordConfig {
+        PasswordConfig::new(length)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+    }
+
+    #[test]
+    fn test_generate_batch_unique() {
+        // every one of the 1000 possible passwords is produced exactly once
+        let config = digits(3);
+        let options = BatchOptions::default().with_threads(4).with_unique(true);
+        let passwords = generate_batch(&config, 1000, options).unwrap();
+        let distinct: HashSet<&str> = passwords.iter().map(|p| p.expose_secret()).collect();
+
+        assert_eq!(passwords.len(), 1000);
+        assert_eq!(distinct.len(), 1000);
+    }
+
+    #[test]
+    fn test_generate_batch_not_enough_unique_passwords() {
+        let config = digits(2);
+        let options = BatchOptions::default().with_unique(true);
+
+        assert!(matches!(
+            generate_batch(&config, 101, options),
+            Err(GenerateError::NotEnoughUniquePasswords { requested: 101, unique: 100 })
+        ));
+    }
+
+    #[test]
+    fn test_generate_batch_to_writer() {
+        let config = PasswordConfig::new(16).unwrap();
+        let mut output = Vec::new();
+        generate_batch_to(&config, 5000, BatchOptions::default(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(output.lines().count(), 5000);
+        assert!(output.lines().all(|line| line.len() == 16));
+    }
+}
@@ -0,0 +1,194 @@
+use alloc::{string::{String, ToString}, vec::Vec};
+use crate::DEFAULT_CHARSETS;
+
+/// Name of the built-in lowercase letters class: a-z
+pub const LOWERCASE: &str = "lowercase";
+/// Name of the built-in uppercase letters class: A-Z
+pub const UPPERCASE: &str = "uppercase";
+/// Name of the built-in numeric digits class: 0-9
+pub const DIGITS: &str = "digits";
+/// Name of the built-in braces class: ()[]{}
+pub const BRACES: &str = "braces";
+/// Name of the built-in punctuation class: .,:;
+pub const PUNCTUATION: &str = "punctuation";
+/// Name of the built-in quotes class: "'
+pub const QUOTES: &str = "quotes";
+/// Name of the built-in dashes class: -/\_|
+pub const DASHES: &str = "dashes";
+/// Name of the built-in math class: !*+<=>?
+pub const MATH: &str = "math";
+/// Name of the built-in logograms class: #$%&@^`~
+pub const LOGOGRAMS: &str = "logograms";
+
+/// Names of the built-in symbol classes
+pub const SYMBOL_CHARSETS: [&str; 6] = [BRACES, PUNCTUATION, QUOTES, DASHES, MATH, LOGOGRAMS];
+
+/// A named character class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Charset {
+    /// Name used to toggle the class
+    pub name: String,
+
+    /// Characters of the class
+    pub chars: String,
+
+    /// Whether the class is used to generate passwords
+    pub enabled: bool,
+
+    /// Minimum number of characters drawn from the class when enabled
+    pub min: usize,
+
+    /// Maximum number of characters drawn from the class when enabled
+    pub max: Option<usize>,
+}
+
+/// Ordered collection of named character classes.
+///
+/// The order is the order in which the classes are drawn by the generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharsetRegistry {
+    charsets: Vec<Charset>,
+
+    /// Names passed to the setters that matched no class, reported by
+    /// `PasswordConfig::validate`
+    unknown: Vec<String>,
+}
+
+impl Default for CharsetRegistry {
+    /// Creates the registry of the nine built-in classes, with lowercase,
+    /// uppercase and digits enabled
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        for (name, chars, enabled) in [
+            (LOWERCASE, DEFAULT_CHARSETS.lowercase, true),
+            (UPPERCASE, DEFAULT_CHARSETS.uppercase, true),
+            (DIGITS, DEFAULT_CHARSETS.digits, true),
+            (BRACES, DEFAULT_CHARSETS.braces, false),
+            (PUNCTUATION, DEFAULT_CHARSETS.punctuation, false),
+            (QUOTES, DEFAULT_CHARSETS.quotes, false),
+            (DASHES, DEFAULT_CHARSETS.dashes, false),
+            (MATH, DEFAULT_CHARSETS.math, false),
+            (LOGOGRAMS, DEFAULT_CHARSETS.logograms, false),
+        ] {
+            registry.register(name, chars);
+            registry.set_enabled(name, enabled);
+        }
+        registry
+    }
+}
+
+impl CharsetRegistry {
+    /// Creates a registry without any class
+    pub const fn empty() -> Self {
+        Self {
+            charsets: Vec::new(),
+            unknown: Vec::new(),
+        }
+    }
+
+    /// Registers a disabled class, or replaces the characters of an existing one
+    pub fn register(&mut self, name: &str, chars: &str) {
+        match self.get_mut(name) {
+            Some(charset) => charset.chars = chars.to_string(),
+            None => self.charsets.push(Charset {
+                name: name.to_string(),
+                chars: chars.to_string(),
+                enabled: false,
+                min: 1,
+                max: None,
+            }),
+        }
+    }
+
+    /// Enables or disables a class. Returns false if no class has this name, in which
+    /// case the name is kept in `unknown_names`
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        self.update(name, |charset| charset.enabled = enabled)
+    }
+
+    /// Sets the minimum number of characters of a class. Returns false if no class has
+    /// this name, in which case the name is kept in `unknown_names`
+    pub fn set_min(&mut self, name: &str, min: usize) -> bool {
+        self.update(name, |charset| charset.min = min)
+    }
+
+    /// Sets the maximum number of characters of a class. Returns false if no class has
+    /// this name, in which case the name is kept in `unknown_names`
+    pub fn set_max(&mut self, name: &str, max: Option<usize>) -> bool {
+        self.update(name, |charset| charset.max = max)
+    }
+
+    fn update(&mut self, name: &str, f: impl FnOnce(&mut Charset)) -> bool {
+        match self.get_mut(name) {
+            Some(charset) => {
+                f(charset);
+                true
+            }
+            None => {
+                if !self.unknown.iter().any(|unknown| unknown == name) {
+                    self.unknown.push(name.to_string());
+                }
+                false
+            }
+        }
+    }
+
+    /// Names passed to `set_enabled`, `set_min` or `set_max` that matched no class
+    pub fn unknown_names(&self) -> impl Iterator<Item = &str> {
+        self.unknown.iter().map(String::as_str)
+    }
+
+    /// Returns true if the class exists and is enabled
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|c| c.enabled)
+    }
+
+    /// Returns the class with this name
+    pub fn get(&self, name: &str) -> Option<&Charset> {
+        self.charsets.iter().find(|c| c.name == name)
+    }
+
+    fn get_mut(&mut self, name: &str) -> Option<&mut Charset> {
+        self.charsets.iter_mut().find(|c| c.name == name)
+    }
+
+    /// Iterates over all the classes
+    pub fn iter(&self) -> impl Iterator<Item = &Charset> {
+        self.charsets.iter()
+    }
+
+    /// Iterates over the enabled classes
+    pub fn enabled(&self) -> impl Iterator<Item = &Charset> {
+        self.charsets.iter().filter(|c| c.enabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_registry() {
+        let registry = CharsetRegistry::default();
+        let enabled: Vec<&str> = registry.enabled().map(|c| c.name.as_str()).collect();
+
+        assert_eq!(registry.iter().count(), 9);
+        assert_eq!(enabled, [LOWERCASE, UPPERCASE, DIGITS]);
+    }
+
+    #[test]
+    fn test_register_custom_charset() {
+        let mut registry = CharsetRegistry::default();
+        registry.register("base58", "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz");
+
+        assert!(!registry.is_enabled("base58"));
+        assert!(registry.set_enabled("base58", true));
+        assert!(registry.is_enabled("base58"));
+        assert!(!registry.set_enabled("cyrillic", true));
+        assert!(registry.unknown_names().eq(["cyrillic"]));
+
+        registry.register("base58", "123");
+        assert_eq!(registry.get("base58").unwrap().chars, "123");
+        assert_eq!(registry.iter().count(), 10);
+    }
+}
@@ -1,15 +1,35 @@
+use alloc::{string::String, vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatExt;
+use crate::charset::{self, CharsetRegistry};
+use crate::generator::{fill_log_weights, graphemes, Group};
+use alloc::collections::{BTreeMap, BTreeSet};
+
 /// Error type for password configuration validation
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum PasswordConfigError {
     ZeroLength,
     NoCharacterSetsEnabled,
     LengthTooShortForSets { length: usize, sets_count: usize },
     NotEnoughAvailableCharacters { length: usize, available: usize },
     PinLengthTooShort,
+    ZeroWords,
+    EmptyWordlist,
+    MinGreaterThanMax { name: String, min: usize, max: usize },
+    LengthTooShortForMinimums { length: usize, required: usize },
+    LengthTooLongForMaximums { length: usize, maximum: usize },
+    ZeroConstraintLimit,
+    NotEnoughDistinctCharacters { length: usize, distinct: usize, max_occurrences: usize },
+    UnavoidableRepetition { length: usize, max_consecutive: usize },
+    UnavoidableSequence { length: usize, max_sequence: usize },
+    EmptyCharacterSet { name: String },
+    UnknownCharacterSet { name: String },
+    MinCountTooHighForOccurrences { name: String, min: usize, distinct: usize, max_occurrences: usize },
+    OverlappingCappedCharacterSet { name: String, other: String },
 }
 
-impl std::fmt::Display for PasswordConfigError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for PasswordConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::ZeroLength => write!(f, "Password length must be greater than 0."),
             Self::NoCharacterSetsEnabled => {
@@ -25,16 +45,83 @@ impl std::fmt::Display for PasswordConfigError {
             Self::NotEnoughAvailableCharacters { length, available } => {
                 write!(
                     f,
-                    "Password length ({}) is too long given the restricted character set ({} available characters after avoiding ambiguous ones).",
+                    "Password length ({}) is too long given the restricted character set ({} available characters after exclusions).",
                     length, available
                 )
             }
             Self::PinLengthTooShort => write!(f, "PIN length must be at least 4 characters."),
+            Self::ZeroWords => write!(f, "Passphrase must contain at least one word."),
+            Self::EmptyWordlist => write!(f, "The wordlist does not contain any word."),
+            Self::MinGreaterThanMax { name, min, max } => {
+                write!(
+                    f,
+                    "Minimum count ({}) of '{}' characters is greater than its maximum count ({}).",
+                    min, name, max
+                )
+            }
+            Self::LengthTooShortForMinimums { length, required } => {
+                write!(
+                    f,
+                    "Password length ({}) is lower than the sum of the minimum counts ({}).",
+                    length, required
+                )
+            }
+            Self::LengthTooLongForMaximums { length, maximum } => {
+                write!(
+                    f,
+                    "Password length ({}) is greater than the sum of the maximum counts ({}).",
+                    length, maximum
+                )
+            }
+            Self::ZeroConstraintLimit => {
+                write!(f, "Repetition, sequence and occurrence limits must be greater than 0.")
+            }
+            Self::NotEnoughDistinctCharacters { length, distinct, max_occurrences } => {
+                write!(
+                    f,
+                    "Password length ({}) is too long for the {} distinct characters left after exclusions, used at most {} times each.",
+                    length, distinct, max_occurrences
+                )
+            }
+            Self::UnavoidableRepetition { length, max_consecutive } => {
+                write!(
+                    f,
+                    "Password length ({}) can not avoid more than {} identical consecutive characters.",
+                    length, max_consecutive
+                )
+            }
+            Self::UnavoidableSequence { length, max_sequence } => {
+                write!(
+                    f,
+                    "Password length ({}) can not avoid sequences longer than {} characters.",
+                    length, max_sequence
+                )
+            }
+            Self::EmptyCharacterSet { name } => {
+                write!(f, "No '{}' character is left after exclusions.", name)
+            }
+            Self::UnknownCharacterSet { name } => {
+                write!(f, "No character set is named '{}'.", name)
+            }
+            Self::MinCountTooHighForOccurrences { name, min, distinct, max_occurrences } => {
+                write!(
+                    f,
+                    "Minimum count ({}) of '{}' characters is too high for its {} distinct characters left after exclusions, used at most {} times each.",
+                    min, name, distinct, max_occurrences
+                )
+            }
+            Self::OverlappingCappedCharacterSet { name, other } => {
+                write!(
+                    f,
+                    "'{}' has a maximum count, so its characters can not also belong to '{}'.",
+                    name, other
+                )
+            }
         }
     }
 }
 
-impl std::error::Error for PasswordConfigError {}
+impl core::error::Error for PasswordConfigError {}
 
 /// Configuration for password generation
 #[derive(Debug, Clone)]
@@ -42,38 +129,23 @@ pub struct PasswordConfig {
     /// Length of the password to generate
     pub length: usize,
 
-    /// Include lowercase letters (a-z)
-    pub include_lowercase: bool,
-
-    /// Include uppercase letters (A-Z)
-    pub include_uppercase: bool,
-
-    /// Include numeric digits (0-9)
-    pub include_digits: bool,
-
-    /// Include braces: ()[]{}
-    pub include_braces: bool,
-
-    /// Include punctuation: .,:;
-    pub include_punctuation: bool,
-
-    /// Include quotes: "'
-    pub include_quotes: bool,
-
-    /// Include dashes: -/\_|
-    pub include_dashes: bool,
-
-    /// Include math: !*+<=>?
-    pub include_math: bool,
-
-    /// Include logograms: #$%&@^`~
-    pub include_logograms: bool,
+    /// Character classes, see `CharsetRegistry`
+    pub charsets: CharsetRegistry,
 
     /// Avoid ambiguous characters (0O1Il5S)
     pub excluded: String,
 
     /// Also include characters
     pub included: String,
+
+    /// Maximum number of identical consecutive characters, e.g. 2 rejects `aaa`
+    pub max_consecutive: Option<usize>,
+
+    /// Maximum length of ascending or descending runs, e.g. 2 rejects `abc` and `321`
+    pub max_sequence: Option<usize>,
+
+    /// Maximum number of occurrences of any character, 1 makes all characters unique
+    pub max_occurrences: Option<usize>,
 }
 
 impl Default for PasswordConfig {
@@ -85,17 +157,12 @@ impl Default for PasswordConfig {
     fn default() -> Self {
         Self {
             length: 10,
-            include_lowercase: true,
-            include_uppercase: true,
-            include_digits: true,
-            include_braces: false,
-            include_punctuation: false,
-            include_quotes: false,
-            include_dashes: false,
-            include_math: false,
-            include_logograms: false,
+            charsets: CharsetRegistry::default(),
             excluded: String::from(""),
             included: String::from(""),
+            max_consecutive: None,
+            max_sequence: None,
+            max_occurrences: None,
         }
     }
 }
@@ -115,56 +182,76 @@ impl PasswordConfig {
     }
 
     /// Builder method to set whether to include lowercase letters
-    pub const fn with_lowercase(mut self, include: bool) -> Self {
-        self.include_lowercase = include;
-        self
+    pub fn with_lowercase(self, include: bool) -> Self {
+        self.with_charset(charset::LOWERCASE, include)
     }
 
     /// Builder method to set whether to include uppercase letters
-    pub const fn with_uppercase(mut self, include: bool) -> Self {
-        self.include_uppercase = include;
-        self
+    pub fn with_uppercase(self, include: bool) -> Self {
+        self.with_charset(charset::UPPERCASE, include)
     }
 
     /// Builder method to set whether to include digits
-    pub const fn with_digits(mut self, include: bool) -> Self {
-        self.include_digits = include;
-        self
+    pub fn with_digits(self, include: bool) -> Self {
+        self.with_charset(charset::DIGITS, include)
     }
 
     /// Builder method to set whether to include braces
-    pub const fn with_braces(mut self, include: bool) -> Self {
-        self.include_braces = include;
-        self
+    pub fn with_braces(self, include: bool) -> Self {
+        self.with_charset(charset::BRACES, include)
     }
 
     /// Builder method to set whether to include punctuation
-    pub const fn with_punctuation(mut self, include: bool) -> Self {
-        self.include_punctuation = include;
-        self
+    pub fn with_punctuation(self, include: bool) -> Self {
+        self.with_charset(charset::PUNCTUATION, include)
     }
 
     /// Builder method to set whether to include quotes
-    pub const fn with_quotes(mut self, include: bool) -> Self {
-        self.include_quotes = include;
-        self
+    pub fn with_quotes(self, include: bool) -> Self {
+        self.with_charset(charset::QUOTES, include)
     }
 
     /// Builder method to set whether to include dashes
-    pub const fn with_dashes(mut self, include: bool) -> Self {
-        self.include_dashes = include;
-        self
+    pub fn with_dashes(self, include: bool) -> Self {
+        self.with_charset(charset::DASHES, include)
     }
 
     /// Builder method to set whether to include math
-    pub const fn with_math(mut self, include: bool) -> Self {
-        self.include_math = include;
-        self
+    pub fn with_math(self, include: bool) -> Self {
+        self.with_charset(charset::MATH, include)
     }
 
     /// Builder method to set whether to include logograms
-    pub const fn with_logograms(mut self, include: bool) -> Self {
-        self.include_logograms = include;
+    pub fn with_logograms(self, include: bool) -> Self {
+        self.with_charset(charset::LOGOGRAMS, include)
+    }
+
+    /// Builder method to enable or disable a registered character class.
+    /// An unknown name makes `validate` fail
+    pub fn with_charset(mut self, name: &str, include: bool) -> Self {
+        self.charsets.set_enabled(name, include);
+        self
+    }
+
+    /// Builder method to register a custom character class and enable it
+    pub fn with_custom_charset(mut self, name: &str, chars: &str) -> Self {
+        self.charsets.register(name, chars);
+        self.charsets.set_enabled(name, true);
+        self
+    }
+
+    /// Builder method to set the minimum number of characters of a class.
+    /// An unknown name makes `validate` fail
+    pub fn with_min_count(mut self, name: &str, min: usize) -> Self {
+        self.charsets.set_min(name, min);
+        self
+    }
+
+    /// Builder method to set the maximum number of characters of a class. The class
+    /// must not share characters with `included` or another enabled class.
+    /// An unknown name makes `validate` fail
+    pub fn with_max_count(mut self, name: &str, max: usize) -> Self {
+        self.charsets.set_max(name, Some(max));
         self
     }
 
@@ -182,26 +269,60 @@ impl PasswordConfig {
 
     }
 
+    /// Builder method to limit the number of identical consecutive characters
+    pub const fn with_max_consecutive(mut self, max: usize) -> Self {
+        self.max_consecutive = Some(max);
+        self
+    }
+
+    /// Builder method to limit the length of ascending or descending runs
+    pub const fn with_max_sequence(mut self, max: usize) -> Self {
+        self.max_sequence = Some(max);
+        self
+    }
+
+    /// Builder method to limit the number of occurrences of any character
+    pub const fn with_max_occurrences(mut self, max: usize) -> Self {
+        self.max_occurrences = Some(max);
+        self
+    }
+
+    /// Builder method to set whether all characters must be unique
+    pub const fn with_unique_chars(mut self, unique: bool) -> Self {
+        self.max_occurrences = if unique { Some(1) } else { None };
+        self
+    }
+
     /// Validates the configuration
-    pub const fn validate(&self) -> Result<(), PasswordConfigError> {
+    pub fn validate(&self) -> Result<(), PasswordConfigError> {
         if self.length == 0 {
             return Err(PasswordConfigError::ZeroLength);
         }
 
-        let sets_count = self.include_lowercase as usize
-            + self.include_uppercase as usize
-            + self.include_digits as usize
-            + self.include_braces as usize
-            + self.include_punctuation as usize
-            + self.include_quotes as usize
-            + self.include_dashes as usize
-            + self.include_math as usize
-            + self.include_logograms as usize;
+        if let Some(name) = self.charsets.unknown_names().next() {
+            return Err(PasswordConfigError::UnknownCharacterSet {
+                name: String::from(name),
+            });
+        }
+
+        let sets_count = self.charsets.enabled().filter(|set| set.min > 0).count();
 
-        if sets_count == 0 {
+        let included = self.included_graphemes();
+        if self.charsets.enabled().count() == 0 && included.is_empty() {
             return Err(PasswordConfigError::NoCharacterSetsEnabled);
         }
 
+        let excluded = self.excluded_graphemes();
+        if let Some(set) = self
+            .charsets
+            .enabled()
+            .find(|set| graphemes(&set.chars).all(|c| excluded.contains(c)))
+        {
+            return Err(PasswordConfigError::EmptyCharacterSet {
+                name: set.name.clone(),
+            });
+        }
+
         if self.length < sets_count {
             return Err(PasswordConfigError::LengthTooShortForSets {
                 length: self.length,
@@ -209,6 +330,574 @@ impl PasswordConfig {
             });
         }
 
+        for set in self.charsets.enabled() {
+            if let Some(max) = set.max.filter(|max| *max < set.min) {
+                return Err(PasswordConfigError::MinGreaterThanMax {
+                    name: set.name.clone(),
+                    min: set.min,
+                    max,
+                });
+            }
+        }
+
+        let required = self.charsets.enabled().map(|set| set.min).sum::<usize>()
+            + !included.is_empty() as usize;
+        if self.length < required {
+            return Err(PasswordConfigError::LengthTooShortForMinimums {
+                length: self.length,
+                required,
+            });
+        }
+
+        self.validate_maximums()?;
+        self.validate_constraints()
+    }
+
+    /// Checks that the maximum counts can be met.
+    ///
+    /// A character drawn for any group counts for every class containing it, so the
+    /// characters of a capped class must not belong to another group: the generator
+    /// only enforces a cap on the draws of its own group.
+    fn validate_maximums(&self) -> Result<(), PasswordConfigError> {
+        let excluded = self.excluded_graphemes();
+        let mut chars: Vec<(&str, BTreeSet<&str>)> =
+            vec![("included", self.included_graphemes().into_iter().collect())];
+        for set in self.charsets.enabled() {
+            chars.push((
+                &set.name,
+                graphemes(&set.chars).filter(|c| !excluded.contains(c)).collect(),
+            ));
+        }
+        for set in self.charsets.enabled().filter(|set| set.max.is_some()) {
+            let (_, capped) = chars.iter().find(|(name, _)| *name == set.name).unwrap();
+            let other = chars
+                .iter()
+                .filter(|(name, _)| *name != set.name)
+                .find(|(_, other)| !capped.is_disjoint(other));
+            if let Some((other, _)) = other {
+                return Err(PasswordConfigError::OverlappingCappedCharacterSet {
+                    name: set.name.clone(),
+                    other: String::from(*other),
+                });
+            }
+        }
+
+        // the fill can only go to the groups with characters of their own, up to their cap
+        let groups = self.groups();
+        let forced: usize = groups.iter().map(|group| group.forced).sum();
+        let capacity = groups
+            .iter()
+            .filter(|group| !group.fill_chars.is_empty())
+            .map(|group| group.fill_cap)
+            .sum::<Option<usize>>();
+        if let Some(maximum) = capacity
+            .map(|capacity| forced + capacity)
+            .filter(|maximum| *maximum < self.length)
+        {
+            return Err(PasswordConfigError::LengthTooLongForMaximums {
+                length: self.length,
+                maximum,
+            });
+        }
         Ok(())
     }
+
+    /// Checks that the repetition, sequence and occurrence limits can be met
+    /// with the available characters
+    fn validate_constraints(&self) -> Result<(), PasswordConfigError> {
+        let limits = [self.max_consecutive, self.max_sequence, self.max_occurrences];
+        if limits.contains(&Some(0)) {
+            return Err(PasswordConfigError::ZeroConstraintLimit);
+        }
+
+        let distinct: Vec<&str> = self.groups().into_iter().flat_map(|g| g.fill_chars).collect();
+
+        if let Some(max_occurrences) = self.max_occurrences {
+            let excluded = self.excluded_graphemes();
+            for set in self.charsets.enabled() {
+                let chars: BTreeSet<&str> =
+                    graphemes(&set.chars).filter(|c| !excluded.contains(c)).collect();
+                if chars.len() * max_occurrences < set.min {
+                    return Err(PasswordConfigError::MinCountTooHighForOccurrences {
+                        name: set.name.clone(),
+                        min: set.min,
+                        distinct: chars.len(),
+                        max_occurrences,
+                    });
+                }
+            }
+
+            if max_occurrences == 1 && distinct.len() < self.length {
+                return Err(PasswordConfigError::NotEnoughAvailableCharacters {
+                    length: self.length,
+                    available: distinct.len(),
+                });
+            }
+            if distinct.len() * max_occurrences < self.length {
+                return Err(PasswordConfigError::NotEnoughDistinctCharacters {
+                    length: self.length,
+                    distinct: distinct.len(),
+                    max_occurrences,
+                });
+            }
+        }
+
+        if let Some(max_consecutive) = self.max_consecutive {
+            if distinct.len() < 2 && self.length > max_consecutive {
+                return Err(PasswordConfigError::UnavoidableRepetition {
+                    length: self.length,
+                    max_consecutive,
+                });
+            }
+        }
+
+        // Alternating two characters never repeats a character twice in a row, and
+        // makes runs of at most 2 characters (only 1 if they are not neighbours)
+        if let Some(max_sequence) = self.max_sequence {
+            let repeat_allowed = self.max_consecutive.is_none_or(|max| max >= self.length);
+            let alternation = distinct.len() >= 2
+                && (max_sequence >= 2
+                    || distinct
+                        .iter()
+                        .any(|a| distinct.iter().any(|b| sequence_step(a, b).is_none() && a != b)));
+            if self.length > max_sequence && !repeat_allowed && !alternation {
+                return Err(PasswordConfigError::UnavoidableSequence {
+                    length: self.length,
+                    max_sequence,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if `password` respects the repetition, sequence and occurrence limits
+    pub fn respects_constraints(&self, password: &str) -> bool {
+        self.respects_constraints_graphemes(&graphemes(password).collect::<Vec<_>>())
+    }
+
+    pub(crate) fn respects_constraints_graphemes(&self, password: &[&str]) -> bool {
+        if let Some(max) = self.max_consecutive {
+            let mut run = 0;
+            for (i, c) in password.iter().enumerate() {
+                run = if i > 0 && password[i - 1] == *c { run + 1 } else { 1 };
+                if run > max {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(max) = self.max_sequence {
+            let mut run = 1;
+            let mut direction = None;
+            for pair in password.windows(2) {
+                let step = sequence_step(pair[0], pair[1]);
+                run = match step {
+                    Some(_) if step == direction => run + 1,
+                    Some(_) => 2,
+                    None => 1,
+                };
+                direction = step;
+                if run > max {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(max) = self.max_occurrences {
+            let mut occurrences: BTreeMap<&str, usize> = BTreeMap::new();
+            for c in password {
+                let count = occurrences.entry(c).or_default();
+                *count += 1;
+                if *count > max {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    fn excluded_graphemes(&self) -> BTreeSet<&str> {
+        graphemes(&self.excluded).collect()
+    }
+
+    /// The `included` characters, without the `excluded` ones
+    fn included_graphemes(&self) -> Vec<&str> {
+        let excluded = self.excluded_graphemes();
+        graphemes(&self.included).filter(|c| !excluded.contains(c)).collect()
+    }
+
+    /// Returns the groups drawn by `generate_password`, in order: the `included` chars,
+    /// then the enabled character sets, all after removing the `excluded` characters.
+    ///
+    /// Characters are grapheme clusters, and each of them is kept once in the fill
+    /// alphabet even when it is repeated or belongs to several groups, in which case
+    /// it counts for the first one.
+    pub(crate) fn groups(&self) -> Vec<Group<'_>> {
+        let excluded = self.excluded_graphemes();
+
+        let mut candidates: Vec<(Vec<&str>, usize, Option<usize>)> =
+            vec![(self.included_graphemes(), 1, None)];
+        for set in self.charsets.enabled() {
+            candidates.push((
+                graphemes(&set.chars).filter(|c| !excluded.contains(c)).collect(),
+                set.min,
+                set.max.map(|max| max.saturating_sub(set.min)),
+            ));
+        }
+
+        let mut seen: BTreeSet<&str> = BTreeSet::new();
+        let mut groups = Vec::new();
+        for (chars, forced, fill_cap) in candidates {
+            let mut distinct = BTreeSet::new();
+            let chars: Vec<&str> = chars.into_iter().filter(|c| distinct.insert(*c)).collect();
+            if chars.is_empty() {
+                continue;
+            }
+            let fill_chars = chars.iter().copied().filter(|c| seen.insert(*c)).collect();
+            groups.push(Group {
+                chars,
+                fill_chars,
+                forced,
+                fill_cap,
+            });
+        }
+        groups
+    }
+
+    /// Returns the entropy in bits of the passwords produced by `generate_password`
+    /// with this configuration.
+    ///
+    /// The value is a guaranteed lower bound (min-entropy): `generate_password` never
+    /// produces any given password with a probability greater than `2^-entropy_bits()`.
+    /// It accounts for the `excluded` characters, the `included` string, and the first
+    /// draws which are forced per group (see `with_min_count`) before the shuffle, and the
+    /// fills rejected because of `with_max_count`.
+    ///
+    /// The passwords rejected because of `max_consecutive`, `max_sequence` or
+    /// `max_occurrences` are not accounted for: with these limits the value is an
+    /// estimate, close to the real entropy unless the limits reject most passwords.
+    pub fn entropy_bits(&self) -> f64 {
+        let groups = self.groups();
+
+        let alphabet: usize = groups.iter().map(|g| g.fill_chars.len()).sum();
+        if alphabet == 0 {
+            return 0.0;
+        }
+
+        let forced_bits: f64 = groups
+            .iter()
+            .map(|group| group.forced as f64 * (group.chars.len() as f64).log2())
+            .sum();
+        let forced: usize = groups.iter().map(|group| group.forced).sum();
+        let remaining = self.length.saturating_sub(forced);
+
+        // the maximum counts reject some fills, which makes the accepted ones more likely
+        let accepted_bits = if groups.iter().any(|group| group.fill_cap.is_some()) {
+            fill_log_weights(&groups, remaining)[0][remaining] / core::f64::consts::LN_2
+        } else {
+            0.0
+        };
+
+        forced_bits + remaining as f64 * (alphabet as f64).log2() + accepted_bits.min(0.0)
+    }
+}
+
+/// Returns +1 or -1 when `b` follows or precedes `a` among the digits,
+/// the lowercase or the uppercase letters
+fn sequence_step(a: &str, b: &str) -> Option<i32> {
+    let (mut a_chars, mut b_chars) = (a.chars(), b.chars());
+    let (a, b) = match (a_chars.next(), a_chars.next(), b_chars.next(), b_chars.next()) {
+        (Some(a), None, Some(b), None) => (a, b),
+        _ => return None,
+    };
+    let same_kind = (a.is_ascii_digit() && b.is_ascii_digit())
+        || (a.is_ascii_lowercase() && b.is_ascii_lowercase())
+        || (a.is_ascii_uppercase() && b.is_ascii_uppercase());
+    let step = b as i32 - a as i32;
+    (same_kind && step.abs() == 1).then_some(step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_bits(config: &PasswordConfig, expected: f64) {
+        let bits = config.entropy_bits();
+        assert!((bits - expected).abs() < 1e-9, "{} != {}", bits, expected);
+    }
+
+    #[test]
+    fn test_entropy_default() {
+        let config = PasswordConfig::default();
+
+        assert_bits(
+            &config,
+            2.0 * 26f64.log2() + 10f64.log2() + 7.0 * 62f64.log2(),
+        );
+    }
+
+    #[test]
+    fn test_entropy_with_excluded() {
+        let config = PasswordConfig::new(4)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .excluded("0".to_string());
+
+        assert_bits(&config, 4.0 * 9f64.log2());
+    }
+
+    #[test]
+    fn test_entropy_with_overlapping_included() {
+        let config = PasswordConfig::new(3)
+            .unwrap()
+            .with_uppercase(false)
+            .with_digits(false)
+            .included("aa".to_string());
+
+        // the included group always yields 'a', which is then drawn like any other letter
+        assert_bits(&config, 2.0 * 26f64.log2());
+    }
+
+    #[test]
+    fn test_entropy_with_custom_charset() {
+        let config = PasswordConfig::new(5)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .with_digits(false)
+            .with_custom_charset("hex", "0123456789abcdef");
+
+        assert_bits(&config, 5.0 * 4.0);
+    }
+
+    #[test]
+    fn test_entropy_with_counts() {
+        let config = PasswordConfig::new(4)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .with_min_count(charset::DIGITS, 2)
+            .with_custom_charset("ab", "ab")
+            .with_min_count("ab", 0)
+            .with_max_count("ab", 1);
+
+        // the two filled chars can not both be 'a' or 'b', which happens with probability 1/36
+        assert_bits(&config, 2.0 * 10f64.log2() + 2.0 * 12f64.log2() + (35f64 / 36.0).log2());
+    }
+
+    #[test]
+    fn test_validate_counts() {
+        let config = PasswordConfig::new(6).unwrap();
+
+        assert_eq!(
+            config.clone().with_min_count(charset::DIGITS, 3).with_max_count(charset::DIGITS, 2).validate(),
+            Err(PasswordConfigError::MinGreaterThanMax {
+                name: charset::DIGITS.to_string(),
+                min: 3,
+                max: 2
+            })
+        );
+        assert_eq!(
+            config.clone().with_min_count(charset::DIGITS, 5).validate(),
+            Err(PasswordConfigError::LengthTooShortForMinimums { length: 6, required: 7 })
+        );
+        assert_eq!(
+            config
+                .clone()
+                .with_max_count(charset::LOWERCASE, 1)
+                .with_max_count(charset::UPPERCASE, 1)
+                .with_max_count(charset::DIGITS, 3)
+                .validate(),
+            Err(PasswordConfigError::LengthTooLongForMaximums { length: 6, maximum: 5 })
+        );
+        assert!(config.with_min_count(charset::LOWERCASE, 0).validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_overlapping_maximums() {
+        let config = PasswordConfig::new(20).unwrap();
+
+        assert_eq!(
+            config.clone().included("5".to_string()).with_max_count(charset::DIGITS, 2).validate(),
+            Err(PasswordConfigError::OverlappingCappedCharacterSet {
+                name: charset::DIGITS.to_string(),
+                other: "included".to_string()
+            })
+        );
+        assert_eq!(
+            config
+                .clone()
+                .with_max_count(charset::DIGITS, 3)
+                .with_custom_charset("0123", "0123")
+                .with_max_count("0123", 10)
+                .validate(),
+            Err(PasswordConfigError::OverlappingCappedCharacterSet {
+                name: charset::DIGITS.to_string(),
+                other: "0123".to_string()
+            })
+        );
+        // the excluded characters are not shared
+        assert!(config
+            .with_max_count(charset::DIGITS, 3)
+            .with_custom_charset("hex", "0123abc")
+            .excluded("0123".to_string())
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_validate_empty_character_set() {
+        let config = PasswordConfig::new(8)
+            .unwrap()
+            .with_quotes(true)
+            .excluded("\"'".to_string());
+
+        assert_eq!(
+            config.validate(),
+            Err(PasswordConfigError::EmptyCharacterSet {
+                name: charset::QUOTES.to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_unknown_character_set() {
+        let unknown = |config: PasswordConfig| {
+            assert_eq!(
+                config.validate(),
+                Err(PasswordConfigError::UnknownCharacterSet {
+                    name: "digit".to_string()
+                })
+            );
+        };
+        let config = PasswordConfig::new(8).unwrap();
+
+        unknown(config.clone().with_charset("digit", true));
+        unknown(config.clone().with_min_count("digit", 2));
+        unknown(config.clone().with_max_count("digit", 2));
+        assert!(config
+            .with_custom_charset("greek", "αβγδ")
+            .with_max_count("greek", 2)
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_entropy_empty_alphabet() {
+        let config = PasswordConfig::new(4)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .excluded(crate::DEFAULT_CHARSETS.digits.to_string());
+
+        assert_bits(&config, 0.0);
+    }
+
+    #[test]
+    fn test_respects_constraints() {
+        let config = PasswordConfig::default()
+            .with_max_consecutive(2)
+            .with_max_sequence(2)
+            .with_max_occurrences(3);
+        let check = |password: &str| config.respects_constraints(password);
+
+        assert!(check("aab12ba"));
+        assert!(!check("aaab"));
+        assert!(!check("xabc"));
+        assert!(!check("x321"));
+        assert!(!check("a1a2a3a"));
+        assert!(check("9:;"));
+    }
+
+    #[test]
+    fn test_validate_constraints() {
+        let digits = PasswordConfig::new(12)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false);
+
+        assert_eq!(
+            digits.clone().with_unique_chars(true).validate(),
+            Err(PasswordConfigError::NotEnoughAvailableCharacters { length: 12, available: 10 })
+        );
+        assert_eq!(
+            PasswordConfig::new(8)
+                .unwrap()
+                .with_lowercase(false)
+                .with_uppercase(false)
+                .excluded("012".to_string())
+                .with_max_occurrences(1)
+                .validate(),
+            Err(PasswordConfigError::NotEnoughAvailableCharacters { length: 8, available: 7 })
+        );
+        assert_eq!(
+            digits
+                .clone()
+                .excluded("01234".to_string())
+                .with_max_occurrences(2)
+                .validate(),
+            Err(PasswordConfigError::NotEnoughDistinctCharacters {
+                length: 12,
+                distinct: 5,
+                max_occurrences: 2
+            })
+        );
+        assert_eq!(
+            digits.clone().with_max_sequence(0).validate(),
+            Err(PasswordConfigError::ZeroConstraintLimit)
+        );
+        assert_eq!(
+            digits
+                .clone()
+                .excluded("01234567".to_string())
+                .with_max_consecutive(1)
+                .with_max_sequence(1)
+                .validate(),
+            Err(PasswordConfigError::UnavoidableSequence { length: 12, max_sequence: 1 })
+        );
+        assert_eq!(
+            digits
+                .clone()
+                .excluded("012345678".to_string())
+                .with_max_consecutive(3)
+                .validate(),
+            Err(PasswordConfigError::UnavoidableRepetition { length: 12, max_consecutive: 3 })
+        );
+        assert!(digits
+            .with_max_consecutive(1)
+            .with_max_sequence(1)
+            .with_max_occurrences(2)
+            .validate()
+            .is_ok());
+        assert_eq!(
+            PasswordConfig::new(20)
+                .unwrap()
+                .with_min_count(charset::DIGITS, 11)
+                .with_unique_chars(true)
+                .validate(),
+            Err(PasswordConfigError::MinCountTooHighForOccurrences {
+                name: charset::DIGITS.to_string(),
+                min: 11,
+                distinct: 10,
+                max_occurrences: 1
+            })
+        );
+        assert_eq!(
+            PasswordConfig::new(20)
+                .unwrap()
+                .with_min_count(charset::DIGITS, 7)
+                .with_max_occurrences(2)
+                .excluded("0123456".to_string())
+                .validate(),
+            Err(PasswordConfigError::MinCountTooHighForOccurrences {
+                name: charset::DIGITS.to_string(),
+                min: 7,
+                distinct: 3,
+                max_occurrences: 2
+            })
+        );
+    }
 }
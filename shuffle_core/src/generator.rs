@@ -1,167 +1,563 @@
-use crate::*;
-use rand::{seq::SliceRandom, Rng, SeedableRng};
+use alloc::collections::BTreeSet;
+use alloc::{string::{String, ToString}, vec, vec::Vec};
+use crate::config::{PasswordConfig, PasswordConfigError};
+use crate::pattern::PatternError;
+use crate::secret::SecretString;
+#[cfg(feature = "health-tests")]
+use crate::health::{new_health_tested_rng, HealthError, HealthTestedRng};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatExt;
+#[cfg(feature = "std")]
+use crate::rng::{check_rng, BackendRng};
+#[cfg(all(feature = "std", not(feature = "health-tests")))]
+use crate::rng::try_new_rng;
+#[cfg(feature = "std")]
+use rand::rand_core::OsError;
+use rand::{seq::SliceRandom, CryptoRng, Rng, RngCore};
 use rand_hc::Hc128Rng;
-use rand_isaac::Isaac64Rng;
+use rand_seeder::Seeder;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// RNG of `try_generate` and `PasswordGenerator`
+#[cfg(feature = "health-tests")]
+type GeneratorRng = HealthTestedRng<BackendRng>;
+#[cfg(all(feature = "std", not(feature = "health-tests")))]
+type GeneratorRng = BackendRng;
+
+/// Creates the RNG of `try_generate` and `PasswordGenerator`. With the `health-tests`
+/// feature, the startup self-test must pass and the seed is health tested
+#[cfg(feature = "std")]
+fn new_generator_rng() -> Result<GeneratorRng, GenerateError> {
+    #[cfg(feature = "health-tests")]
+    {
+        new_health_tested_rng().map_err(GenerateError::HealthTestFailed)
+    }
+    #[cfg(not(feature = "health-tests"))]
+    {
+        try_new_rng().map_err(GenerateError::EntropyUnavailable)
+    }
+}
 
-/// Generate a password
+/// Fails closed when the OS source failed while drawing from `rng`, or a health
+/// test of `rng` failed
+#[cfg(feature = "std")]
+fn check_generator_rng(rng: &GeneratorRng) -> Result<(), GenerateError> {
+    #[cfg(feature = "health-tests")]
+    {
+        check_rng(rng.get_ref()).map_err(GenerateError::EntropyUnavailable)?;
+        rng.check().map_err(GenerateError::HealthTestFailed)
+    }
+    #[cfg(not(feature = "health-tests"))]
+    {
+        check_rng(rng).map_err(GenerateError::EntropyUnavailable)
+    }
+}
+
+/// Runs `generate` with a new RNG of `try_generate`, and fails closed when a health
+/// test failed. Every generator without an RNG argument draws through this
+#[cfg(feature = "std")]
+pub(crate) fn with_generator_rng<T>(
+    generate: impl FnOnce(&mut GeneratorRng) -> Result<T, GenerateError>,
+) -> Result<T, GenerateError> {
+    let mut rng = new_generator_rng()?;
+    let value = generate(&mut rng)?;
+    check_generator_rng(&rng)?;
+    Ok(value)
+}
+
+/// Number of passwords drawn before giving up on the repetition, sequence and
+/// occurrence limits
+pub const MAX_ATTEMPTS: usize = 100_000;
+
+/// Error type for password generation
+#[derive(Debug)]
+pub enum GenerateError {
+    /// The configuration is invalid
+    InvalidConfig(PasswordConfigError),
+    /// The pattern is invalid
+    InvalidPattern(PatternError),
+    /// The OS entropy source could not seed the RNG, or failed while `rng-os` drew
+    /// from it
+    #[cfg(feature = "std")]
+    EntropyUnavailable(OsError),
+    /// No drawn password satisfied the repetition, sequence and occurrence limits
+    ConstraintsUnsatisfiable { attempts: usize },
+    /// A batch asked for more distinct passwords than could be found
+    NotEnoughUniquePasswords { requested: usize, unique: usize },
+    /// Writing the passwords failed
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    /// The RNG failed its startup self-test or a continuous health test
+    #[cfg(feature = "health-tests")]
+    HealthTestFailed(HealthError),
+}
+
+impl core::fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidConfig(e) => write!(f, "{}", e),
+            Self::InvalidPattern(e) => write!(f, "{}", e),
+            #[cfg(feature = "std")]
+            Self::EntropyUnavailable(e) => write!(f, "OS entropy source unavailable: {}", e),
+            Self::ConstraintsUnsatisfiable { attempts } => write!(
+                f,
+                "No password satisfying the repetition, sequence and occurrence limits was found after {} attempts.",
+                attempts
+            ),
+            Self::NotEnoughUniquePasswords { requested, unique } => write!(
+                f,
+                "Only {} distinct passwords were found out of the {} requested.",
+                unique, requested
+            ),
+            #[cfg(feature = "std")]
+            Self::Io(e) => write!(f, "{}", e),
+            #[cfg(feature = "health-tests")]
+            Self::HealthTestFailed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl core::error::Error for GenerateError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::InvalidConfig(e) => Some(e),
+            Self::InvalidPattern(e) => Some(e),
+            #[cfg(feature = "std")]
+            Self::EntropyUnavailable(e) => Some(e),
+            #[cfg(feature = "std")]
+            Self::Io(e) => Some(e),
+            #[cfg(feature = "health-tests")]
+            Self::HealthTestFailed(e) => Some(e),
+            Self::ConstraintsUnsatisfiable { .. } | Self::NotEnoughUniquePasswords { .. } => None,
+        }
+    }
+}
+
+impl From<PasswordConfigError> for GenerateError {
+    fn from(e: PasswordConfigError) -> Self {
+        Self::InvalidConfig(e)
+    }
+}
+
+impl From<PatternError> for GenerateError {
+    fn from(e: PatternError) -> Self {
+        Self::InvalidPattern(e)
+    }
+}
+
+/// Create a reproducible RNG from any `seed`.
+///
+/// **INSECURE**: the same seed always yields the same passwords. Only use it
+/// for tests, fixtures and snapshots, never to generate real credentials.
 ///
 /// # Examples
 /// ```
-/// # use shuffle_core::{generate_password, PasswordConfig};
+/// # use shuffle_core::{generate_password_with_rng, insecure_seeded_rng, PasswordConfig};
 ///
-///  let config = PasswordConfig::new(32).unwrap();
-///  config.include_digits;
-///  config.include_lowercase;
-///  let password = generate_password(&config);
-/// assert_eq!(password.len(), 32);
+///  let config = PasswordConfig::new(16).unwrap();
+///  let first = generate_password_with_rng(&config, &mut insecure_seeded_rng("fixture")).unwrap();
+///  let second = generate_password_with_rng(&config, &mut insecure_seeded_rng("fixture")).unwrap();
+/// assert_eq!(first, second);
 /// ```
-pub fn generate_password(config: &PasswordConfig) -> String {
-    let mut isaac_seeder = Isaac64Rng::from_os_rng();
-    let mut rng = Hc128Rng::from_rng(&mut isaac_seeder);
-
-    let mut charset = String::new();
-    let mut password = Vec::with_capacity(config.length);
+pub fn insecure_seeded_rng(seed: &str) -> Hc128Rng {
+    Seeder::from(seed).into_rng()
+}
 
-    let filtered_lowercase: String = DEFAULT_CHARSETS
-        .lowercase
-        .chars()
-        .filter(|c| !config.excluded.contains(*c))
-        .collect();
+/// Splits `s` into its extended grapheme clusters, so that a character and its
+/// combining marks (e.g. `e` followed by U+0301) are always drawn together
+pub(crate) fn graphemes(s: &str) -> impl Iterator<Item = &str> {
+    s.graphemes(true)
+}
 
-    let filtered_uppercase: String = DEFAULT_CHARSETS
-        .uppercase
-        .chars()
-        .filter(|c| !config.excluded.contains(*c))
-        .collect();
+/// A group of characters drawn by `generate_password`
+pub(crate) struct Group<'a> {
+    /// Distinct characters of the group, used for the forced draws
+    pub(crate) chars: Vec<&'a str>,
+    /// Characters of the group which do not belong to a previous group, used for the
+    /// fill: together, the groups' `fill_chars` hold every available character once
+    pub(crate) fill_chars: Vec<&'a str>,
+    /// Number of characters always drawn from the group
+    pub(crate) forced: usize,
+    /// Maximum number of characters drawn from the group during the fill
+    pub(crate) fill_cap: Option<usize>,
+}
 
-    let filtered_digits: String = DEFAULT_CHARSETS
-        .digits
-        .chars()
-        .filter(|c| !config.excluded.contains(*c))
-        .collect();
+impl Group<'_> {
+    /// Maximum number of characters drawn from the group when `r` are left to fill
+    fn fill_limit(&self, r: usize) -> usize {
+        if self.fill_chars.is_empty() {
+            return 0;
+        }
+        self.fill_cap.map_or(r, |cap| cap.min(r))
+    }
+}
 
-    let filtered_braces: String = DEFAULT_CHARSETS
-        .braces
-        .chars()
-        .filter(|c| !config.excluded.contains(*c))
-        .collect();
+/// Returns `table[i][r]`: the natural log of the probability that, when `r` characters
+/// are drawn uniformly from all the available characters, the draws that land in
+/// `groups[i..]` respect their `fill_cap`, given that none of them land in `groups[..i]`.
+///
+/// `table[0][fill]` is the log probability that a fill respects all the caps.
+pub(crate) fn fill_log_weights(groups: &[Group], fill: usize) -> Vec<Vec<f64>> {
+    let total: usize = groups.iter().map(|g| g.fill_chars.len()).sum();
+    let ln_binomial = ln_binomial_table(fill);
+
+    let mut table = vec![vec![f64::NEG_INFINITY; fill + 1]; groups.len() + 1];
+    table[groups.len()][0] = 0.0;
+
+    for (i, group) in groups.iter().enumerate().rev() {
+        let ln_p = (group.fill_chars.len() as f64 / total as f64).ln();
+        for r in 0..=fill {
+            let max_n = group.fill_limit(r);
+            let terms: Vec<f64> = (0..=max_n)
+                .map(|n| ln_binomial(r, n) + ln_power(ln_p, n) + table[i + 1][r - n])
+                .collect();
+            table[i][r] = log_sum_exp(&terms);
+        }
+    }
+    table
+}
 
-    let filtered_punctuation: String = DEFAULT_CHARSETS
-        .punctuation
-        .chars()
-        .filter(|c| !config.excluded.contains(*c))
+/// Returns a function computing `ln(r choose n)` for `n <= r <= max`
+fn ln_binomial_table(max: usize) -> impl Fn(usize, usize) -> f64 {
+    let ln_factorial: Vec<f64> = core::iter::once(0.0)
+        .chain((1..=max).scan(0.0, |acc, n| {
+            *acc += (n as f64).ln();
+            Some(*acc)
+        }))
         .collect();
+    move |r, n| ln_factorial[r] - ln_factorial[n] - ln_factorial[r - n]
+}
 
-    let filtered_quotes: String = DEFAULT_CHARSETS
-        .quotes
-        .chars()
-        .filter(|c| !config.excluded.contains(*c))
-        .collect();
+/// `n * ln_p`, where `ln_p` may be `-inf` for an empty group (`p^0` is 1)
+fn ln_power(ln_p: f64, n: usize) -> f64 {
+    if n == 0 {
+        0.0
+    } else {
+        n as f64 * ln_p
+    }
+}
 
-    let filtered_dashes: String = DEFAULT_CHARSETS
-        .dashes
-        .chars()
-        .filter(|c| !config.excluded.contains(*c))
-        .collect();
+fn log_sum_exp(terms: &[f64]) -> f64 {
+    let max = terms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + terms.iter().map(|t| (t - max).exp()).sum::<f64>().ln()
+}
 
-    let filtered_math: String = DEFAULT_CHARSETS
-        .math
-        .chars()
-        .filter(|c| !config.excluded.contains(*c))
-        .collect();
+/// Draws how many of the `fill` characters come from each group, following the
+/// distribution of independent uniform draws among all the available characters,
+/// conditioned on every `fill_cap` being respected.
+///
+/// `table` is `fill_log_weights(groups, fill)`, and the caps must be satisfiable.
+fn sample_fill_counts<R: Rng + ?Sized>(
+    groups: &[Group],
+    fill: usize,
+    table: &[Vec<f64>],
+    rng: &mut R,
+) -> Vec<usize> {
+    let total: usize = groups.iter().map(|g| g.fill_chars.len()).sum();
+    let ln_binomial = ln_binomial_table(fill);
+    let mut counts = Vec::with_capacity(groups.len());
+    let mut r = fill;
+    for (i, group) in groups.iter().enumerate() {
+        let ln_p = (group.fill_chars.len() as f64 / total as f64).ln();
+        let max_n = group.fill_limit(r);
+        let mut u: f64 = rng.random();
+        let mut count = max_n;
+        for n in 0..=max_n {
+            let weight =
+                (ln_binomial(r, n) + ln_power(ln_p, n) + table[i + 1][r - n] - table[i][r]).exp();
+            if u < weight {
+                count = n;
+                break;
+            }
+            u -= weight;
+        }
+        // the last group takes everything left
+        if i == groups.len() - 1 {
+            count = r;
+        }
+        counts.push(count);
+        r -= count;
+    }
+    counts
+}
 
-    let filtered_logograms: String = DEFAULT_CHARSETS
-        .logograms
-        .chars()
-        .filter(|c| !config.excluded.contains(*c))
-        .collect();
+/// Picks the characters of a password. With unique characters, they are drawn
+/// without replacement
+struct Picker<'a> {
+    unique: bool,
+    used: BTreeSet<&'a str>,
+}
 
-    //////////////////
-    if !config.included.is_empty() {
-        let included_chars: Vec<char> = config.included.chars().collect();
-        password.push(included_chars[rng.random_range(0..included_chars.len())]);
-        charset.push_str(&config.included);
+impl<'a> Picker<'a> {
+    fn new(unique: bool) -> Self {
+        Self {
+            unique,
+            used: BTreeSet::new(),
+        }
     }
 
-    if config.include_lowercase && !filtered_lowercase.is_empty() {
-        let lowercase_chars: Vec<char> = filtered_lowercase.chars().collect();
-        password.push(lowercase_chars[rng.random_range(0..lowercase_chars.len())]);
-        charset.push_str(&filtered_lowercase);
+    /// Draws a character of `chars`, or `None` if all of them were already used
+    fn pick<R: Rng + ?Sized>(&mut self, chars: &[&'a str], rng: &mut R) -> Option<&'a str> {
+        if !self.unique {
+            return Some(chars[rng.random_range(0..chars.len())]);
+        }
+        let available: Vec<&'a str> =
+            chars.iter().copied().filter(|c| !self.used.contains(c)).collect();
+        if available.is_empty() {
+            return None;
+        }
+        let c = available[rng.random_range(0..available.len())];
+        self.used.insert(c);
+        Some(c)
     }
+}
 
-    if config.include_uppercase && !filtered_uppercase.is_empty() {
-        let uppercase_chars: Vec<char> = filtered_uppercase.chars().collect();
-        password.push(uppercase_chars[rng.random_range(0..uppercase_chars.len())]);
-        charset.push_str(&filtered_uppercase);
-    }
+/// The alphabets of a validated configuration, computed once and reused for every draw
+pub(crate) struct Alphabet<'a> {
+    groups: Vec<Group<'a>>,
+    /// Whether every character must be unique, see `Picker`
+    unique: bool,
+    /// Every available character once, used when no group is capped
+    fill_chars: Vec<&'a str>,
+    /// Number of characters drawn after the forced ones
+    fill: usize,
+    /// `fill_log_weights` of the groups, when a group is capped
+    weights: Option<Vec<Vec<f64>>>,
+}
 
-    if config.include_digits && !filtered_digits.is_empty() {
-        let digit_chars: Vec<char> = filtered_digits.chars().collect();
-        password.push(digit_chars[rng.random_range(0..digit_chars.len())]);
-        charset.push_str(&filtered_digits);
-    }
+impl<'a> Alphabet<'a> {
+    pub(crate) fn new(config: &'a PasswordConfig) -> Self {
+        let groups = config.groups();
+        let forced: usize = groups.iter().map(|g| g.forced).sum();
+        let fill = config.length.saturating_sub(forced);
+        let fill_chars = groups.iter().flat_map(|g| g.fill_chars.iter().copied()).collect();
+
+        // `validate` checked that the caps can be met
+        let weights = if groups.iter().all(|g| g.fill_cap.is_none()) {
+            None
+        } else {
+            Some(fill_log_weights(&groups, fill))
+        };
 
-    if config.include_braces && !filtered_braces.is_empty() {
-        let brace_chars: Vec<char> = filtered_braces.chars().collect();
-        password.push(brace_chars[rng.random_range(0..brace_chars.len())]);
-        charset.push_str(&filtered_braces);
+        Self {
+            groups,
+            unique: config.max_occurrences == Some(1),
+            fill_chars,
+            fill,
+            weights,
+        }
     }
 
-    if config.include_punctuation && !filtered_punctuation.is_empty() {
-        let punctuation_chars: Vec<char> = filtered_punctuation.chars().collect();
-        password.push(punctuation_chars[rng.random_range(0..punctuation_chars.len())]);
-        charset.push_str(&filtered_punctuation);
+    /// Draws one password, ignoring the repetition, sequence and occurrence limits
+    /// except unique characters. Returns `None` when a group has no unused character left
+    pub(crate) fn draw<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<&'a str>> {
+        let mut password = Vec::with_capacity(self.fill + self.groups.len());
+        let mut picker = Picker::new(self.unique);
+
+        for group in &self.groups {
+            for _ in 0..group.forced {
+                password.push(picker.pick(&group.chars, rng)?);
+            }
+        }
+
+        match &self.weights {
+            Some(table) => {
+                let counts = sample_fill_counts(&self.groups, self.fill, table, rng);
+                for (group, count) in self.groups.iter().zip(counts) {
+                    for _ in 0..count {
+                        password.push(picker.pick(&group.fill_chars, rng)?);
+                    }
+                }
+            }
+            None => {
+                for _ in 0..self.fill {
+                    password.push(picker.pick(&self.fill_chars, rng)?);
+                }
+            }
+        }
+
+        password.shuffle(rng);
+
+        Some(password)
     }
+}
+
+/// Generate a password, reporting every failure as a `GenerateError`
+///
+/// # Examples
+/// ```
+/// # use shuffle_core::{try_generate, PasswordConfig};
+///
+///  let config = PasswordConfig::new(32).unwrap().with_math(true);
+///  let password = try_generate(&config).unwrap();
+/// assert_eq!(password.expose_secret().len(), 32);
+/// ```
+#[cfg(feature = "std")]
+pub fn try_generate(config: &PasswordConfig) -> Result<SecretString, GenerateError> {
+    with_generator_rng(|rng| try_generate_with_rng(config, rng))
+}
 
-    if config.include_quotes && !filtered_quotes.is_empty() {
-        let quote_chars: Vec<char> = filtered_quotes.chars().collect();
-        password.push(quote_chars[rng.random_range(0..quote_chars.len())]);
-        charset.push_str(&filtered_quotes);
+/// Generate a password using the given RNG
+///
+/// The configuration is validated first (see `PasswordConfig::validate`). The
+/// repetition, sequence and occurrence limits are met by drawing new passwords
+/// until one satisfies them, at most `MAX_ATTEMPTS` times.
+pub fn try_generate_with_rng<R: CryptoRng + RngCore + ?Sized>(
+    config: &PasswordConfig,
+    rng: &mut R,
+) -> Result<SecretString, GenerateError> {
+    config.validate()?;
+    draw_accepted(config, &Alphabet::new(config), rng)
+}
+
+/// Number of passwords a `PasswordGenerator` draws before reseeding its RNG from the OS
+#[cfg(feature = "std")]
+pub const RESEED_INTERVAL: usize = 1 << 16;
+
+/// Generates many passwords from the same configuration.
+///
+/// The configuration is validated and its alphabets are computed once. The
+/// generator owns its RNG, seeded like `generate_password`'s, and reseeds it from
+/// the OS every `RESEED_INTERVAL` passwords (see `with_reseed_interval`).
+///
+/// As an iterator it yields the result of `next_password`, and ends after the first
+/// error.
+///
+/// # Examples
+/// ```
+/// # use shuffle_core::{PasswordConfig, PasswordGenerator};
+///
+///  let config = PasswordConfig::new(16).unwrap();
+///  let passwords: Result<Vec<_>, _> = PasswordGenerator::new(&config).unwrap().take(100).collect();
+/// assert_eq!(passwords.unwrap().len(), 100);
+/// ```
+#[cfg(feature = "std")]
+pub struct PasswordGenerator<'a> {
+    config: &'a PasswordConfig,
+    alphabet: Alphabet<'a>,
+    rng: GeneratorRng,
+    reseed_interval: usize,
+    generated: usize,
+    failed: bool,
+}
+
+#[cfg(feature = "std")]
+impl<'a> PasswordGenerator<'a> {
+    /// Creates a generator for a valid configuration
+    pub fn new(config: &'a PasswordConfig) -> Result<Self, GenerateError> {
+        config.validate()?;
+
+        Ok(Self {
+            config,
+            alphabet: Alphabet::new(config),
+            rng: new_generator_rng()?,
+            reseed_interval: RESEED_INTERVAL,
+            generated: 0,
+            failed: false,
+        })
     }
 
-    if config.include_dashes && !filtered_dashes.is_empty() {
-        let dash_chars: Vec<char> = filtered_dashes.chars().collect();
-        password.push(dash_chars[rng.random_range(0..dash_chars.len())]);
-        charset.push_str(&filtered_dashes);
+    /// Builder method to set the number of passwords drawn between two reseeds
+    pub fn with_reseed_interval(mut self, passwords: usize) -> Self {
+        self.reseed_interval = passwords.max(1);
+        self
     }
 
-    if config.include_math && !filtered_math.is_empty() {
-        let math_chars: Vec<char> = filtered_math.chars().collect();
-        password.push(math_chars[rng.random_range(0..math_chars.len())]);
-        charset.push_str(&filtered_math);
+    /// Generates the next password
+    pub fn next_password(&mut self) -> Result<SecretString, GenerateError> {
+        check_generator_rng(&self.rng)?;
+        if self.generated == self.reseed_interval {
+            self.rng = new_generator_rng()?;
+            self.generated = 0;
+        }
+        self.generated += 1;
+
+        let password = draw_accepted(self.config, &self.alphabet, &mut self.rng)?;
+        check_generator_rng(&self.rng)?;
+        Ok(password)
     }
+}
 
-    if config.include_logograms && !filtered_logograms.is_empty() {
-        let logogram_chars: Vec<char> = filtered_logograms.chars().collect();
-        password.push(logogram_chars[rng.random_range(0..logogram_chars.len())]);
-        charset.push_str(&filtered_logograms);
+#[cfg(feature = "std")]
+impl Iterator for PasswordGenerator<'_> {
+    type Item = Result<SecretString, GenerateError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let password = self.next_password();
+        self.failed = password.is_err();
+        Some(password)
     }
+}
 
-    let charset_chars: Vec<char> = charset.chars().collect();
+#[cfg(feature = "std")]
+impl core::iter::FusedIterator for PasswordGenerator<'_> {}
 
-    // Fill remaining characters
-    while password.len() < config.length.into() {
-        let c = {
-            *charset_chars
-                .get(rng.random_range(0..charset_chars.len()))
-                .unwrap_or(&'~') // Fallback if empty
+/// Draws passwords until one respects the repetition, sequence and occurrence limits.
+///
+/// Rejection sampling keeps the accepted passwords uniformly distributed among the
+/// ones the unconstrained generator could produce. Unique characters are the only
+/// limit met while drawing: drawing without replacement is exact for them, and does
+/// not reject almost every password of a long length.
+fn draw_accepted<R: Rng + ?Sized>(
+    config: &PasswordConfig,
+    alphabet: &Alphabet,
+    rng: &mut R,
+) -> Result<SecretString, GenerateError> {
+    for _ in 0..MAX_ATTEMPTS {
+        let Some(password) = alphabet.draw(rng) else {
+            continue;
         };
-        password.push(c);
+        if config.respects_constraints_graphemes(&password) {
+            return Ok(SecretString::from(password.concat()));
+        }
     }
 
-    password.shuffle(&mut rng);
+    Err(GenerateError::ConstraintsUnsatisfiable {
+        attempts: MAX_ATTEMPTS,
+    })
+}
 
-    password.into_iter().collect()
+/// Generate a password
+///
+/// The returned `String` is not wiped from memory, prefer `try_generate`.
+///
+/// # Examples
+/// ```
+/// # use shuffle_core::{generate_password, PasswordConfig};
+///
+///  let config = PasswordConfig::new(32).unwrap().with_math(true);
+///  let password = generate_password(&config).unwrap();
+/// assert_eq!(password.len(), 32);
+/// ```
+#[cfg(feature = "std")]
+pub fn generate_password(config: &PasswordConfig) -> Result<String, GenerateError> {
+    try_generate(config).map(|password| password.expose_secret().to_string())
+}
+
+/// Generate a password using the given RNG
+pub fn generate_password_with_rng<R: CryptoRng + RngCore + ?Sized>(
+    config: &PasswordConfig,
+    rng: &mut R,
+) -> Result<String, GenerateError> {
+    try_generate_with_rng(config, rng).map(|password| password.expose_secret().to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{charset, DEFAULT_CHARSETS};
+    use std::collections::HashMap;
 
     #[test]
     fn test_generate_password() {
         let config = PasswordConfig::new(16).unwrap();
-        let password = generate_password(&config);
+        let password = generate_password(&config).unwrap();
 
         assert_eq!(password.len(), 16);
     }
@@ -173,26 +569,338 @@ mod tests {
             included: "é".to_string(),
             ..Default::default()
         };
-        let password = generate_password(&config);
+        let password = generate_password(&config).unwrap();
 
         assert!(password.contains('é'));
     }
     #[test]
     fn test_generate_password_with_included() {
         let config = PasswordConfig::new(16).unwrap().with_digits(true).included("é".to_string());
-        let password = generate_password(&config);
+        let password = generate_password(&config).unwrap();
 
         assert!(password.contains('é'));
 
     }
 
+    #[test]
+    fn test_generate_password_with_excluded_included() {
+        let config = PasswordConfig::new(16)
+            .unwrap()
+            .with_digits(true)
+            .included("5é".to_string())
+            .excluded("5".to_string());
+
+        for _ in 0..50 {
+            let password = generate_password(&config).unwrap();
+            assert!(password.contains('é'));
+            assert!(!password.contains('5'));
+        }
+    }
+
     #[test]
     fn test_generate_password_with_symbols() {
         let config = PasswordConfig::new(16)
             .unwrap()
             .with_logograms(true);
-        let password = generate_password(&config);
+        let password = generate_password(&config).unwrap();
 
         assert_eq!(password.len(), 16);
     }
+
+    #[test]
+    fn test_generate_password_with_custom_charset() {
+        let config = PasswordConfig::new(16)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .with_digits(false)
+            .with_custom_charset("cyrillic", "абвгдежз");
+        let password = generate_password(&config).unwrap();
+
+        assert_eq!(password.chars().count(), 16);
+        assert!(password.chars().all(|c| "абвгдежз".contains(c)));
+    }
+
+    #[test]
+    fn test_generate_password_with_counts() {
+        let config = PasswordConfig::new(20)
+            .unwrap()
+            .with_min_count(charset::DIGITS, 4)
+            .with_logograms(true)
+            .with_min_count(charset::LOGOGRAMS, 2)
+            .with_max_count(charset::LOGOGRAMS, 3);
+
+        for _ in 0..100 {
+            let password = generate_password(&config).unwrap();
+            let digits = password.chars().filter(|c| c.is_ascii_digit()).count();
+            let logograms = password.chars().filter(|c| "#$%&@^`~".contains(*c)).count();
+
+            assert_eq!(password.len(), 20);
+            assert!(digits >= 4);
+            assert!((2..=3).contains(&logograms));
+        }
+    }
+
+    #[test]
+    fn test_generate_password_with_included_and_max_count() {
+        let config = PasswordConfig::new(20)
+            .unwrap()
+            .included("é".to_string())
+            .with_max_count(charset::DIGITS, 2);
+
+        for _ in 0..100 {
+            let password = generate_password(&config).unwrap();
+
+            assert!(password.contains('é'));
+            assert!((1..=2).contains(&password.chars().filter(|c| c.is_ascii_digit()).count()));
+        }
+    }
+
+    #[test]
+    fn test_fill_counts_are_unbiased() {
+        // Two groups of equal size, the second capped at 1: among the fills of
+        // 2 chars, only (2, 0) and (1, 1) are allowed, with probabilities 1/3 and 2/3
+        let groups = [
+            Group { chars: vec!["a"], fill_chars: vec!["a"], forced: 0, fill_cap: None },
+            Group { chars: vec!["b"], fill_chars: vec!["b"], forced: 0, fill_cap: Some(1) },
+        ];
+        let table = fill_log_weights(&groups, 2);
+        let mut rng = insecure_seeded_rng("fill");
+        let runs = 30_000;
+        let ones = (0..runs)
+            .filter(|_| sample_fill_counts(&groups, 2, &table, &mut rng)[1] == 1)
+            .count();
+
+        assert!((ones as f64 / runs as f64 - 2.0 / 3.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_generate_password_with_constraints() {
+        let config = PasswordConfig::new(12)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .with_max_consecutive(1)
+            .with_max_sequence(2)
+            .with_max_occurrences(3);
+
+        for _ in 0..100 {
+            let password: Vec<char> = generate_password(&config).unwrap().chars().collect();
+
+            let steps: Vec<i32> = password.windows(2).map(|w| w[1] as i32 - w[0] as i32).collect();
+
+            assert!(steps.iter().all(|step| *step != 0));
+            assert!(steps.windows(2).all(|w| !(w[0] == w[1] && w[0].abs() == 1)));
+            for digit in DEFAULT_CHARSETS.digits.chars() {
+                assert!(password.iter().filter(|c| **c == digit).count() <= 3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_password_with_unique_chars() {
+        let config = PasswordConfig::new(20).unwrap().with_unique_chars(true);
+        let mut password: Vec<char> = generate_password(&config).unwrap().chars().collect();
+        password.sort_unstable();
+        password.dedup();
+
+        assert_eq!(password.len(), 20);
+    }
+
+    #[test]
+    fn test_generate_password_with_all_chars_unique() {
+        // every password uses the 62 characters once
+        let config = PasswordConfig::new(62).unwrap().with_unique_chars(true);
+        let mut password: Vec<char> = generate_password(&config).unwrap().chars().collect();
+        password.sort_unstable();
+        password.dedup();
+
+        assert_eq!(password.len(), 62);
+    }
+
+    #[test]
+    fn test_generate_password_with_grapheme_clusters() {
+        let config = PasswordConfig::new(8)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .with_digits(false)
+            .included("e\u{301}x".to_string());
+        let password = generate_password(&config).unwrap();
+
+        assert_eq!(graphemes(&password).count(), 8);
+        assert!(graphemes(&password).all(|g| g == "e\u{301}" || g == "x"));
+    }
+
+    /// Pearson's chi-squared statistic of `counts` against the `expected` counts
+    fn chi_squared<K>(counts: &HashMap<K, f64>, expected: impl Fn(&K) -> f64) -> f64 {
+        counts
+            .iter()
+            .map(|(c, observed)| (observed - expected(c)).powi(2) / expected(c))
+            .sum()
+    }
+
+    fn count_chars(config: &PasswordConfig, runs: usize) -> HashMap<char, f64> {
+        let mut rng = insecure_seeded_rng("uniformity");
+        let mut counts = HashMap::new();
+        for _ in 0..runs {
+            for c in generate_password_with_rng(config, &mut rng).unwrap().chars() {
+                *counts.entry(c).or_insert(0.0) += 1.0;
+            }
+        }
+        counts
+    }
+
+    // 99.9th percentile of the chi-squared distribution with 25 degrees of freedom
+    const CHI_SQUARED_25_999: f64 = 52.62;
+
+    #[test]
+    fn test_overlapping_sets_are_uniform() {
+        // every letter of "abc" belongs to both sets, they must not be drawn more often
+        let config = PasswordConfig::new(26)
+            .unwrap()
+            .with_uppercase(false)
+            .with_digits(false)
+            .with_min_count(charset::LOWERCASE, 0)
+            .with_custom_charset("abc", "abc")
+            .with_min_count("abc", 0);
+        let runs = 2000;
+        let counts = count_chars(&config, runs);
+
+        assert_eq!(counts.len(), 26);
+        assert!(chi_squared(&counts, |_| runs as f64) < CHI_SQUARED_25_999);
+    }
+
+    #[test]
+    fn test_duplicated_included_chars_are_uniform() {
+        // 'a' is forced once per password by the included group, then drawn like any letter
+        let config = PasswordConfig::new(26)
+            .unwrap()
+            .with_uppercase(false)
+            .with_digits(false)
+            .with_min_count(charset::LOWERCASE, 0)
+            .included("aaaa".to_string());
+        let runs = 2000;
+        let counts = count_chars(&config, runs);
+        let fill = runs as f64 * 25.0 / 26.0;
+
+        assert_eq!(counts.len(), 26);
+        assert!(chi_squared(&counts, |c| if *c == 'a' { runs as f64 + fill } else { fill }) < CHI_SQUARED_25_999);
+    }
+
+    // 99.9th percentile of the chi-squared distribution with 23 degrees of freedom
+    const CHI_SQUARED_23_999: f64 = 49.73;
+
+    #[test]
+    fn test_max_occurrences_are_uniform() {
+        // 24 of the 27 passwords of "abc" use no character 3 times, each has 1/24 chance
+        let config = PasswordConfig::new(3)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .with_digits(false)
+            .with_custom_charset("abc", "abc")
+            .with_max_occurrences(2);
+        let mut rng = insecure_seeded_rng("occurrences");
+        let runs = 24_000;
+        let mut counts = HashMap::new();
+        for _ in 0..runs {
+            let password = generate_password_with_rng(&config, &mut rng).unwrap();
+            *counts.entry(password).or_insert(0.0) += 1.0;
+        }
+
+        assert_eq!(counts.len(), 24);
+        assert!(chi_squared(&counts, |_| runs as f64 / 24.0) < CHI_SQUARED_23_999);
+    }
+
+    #[test]
+    fn test_generate_password_with_invalid_config() {
+        let config = PasswordConfig::new(8)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .excluded(DEFAULT_CHARSETS.digits.to_string());
+
+        assert!(matches!(
+            try_generate(&config),
+            Err(GenerateError::InvalidConfig(PasswordConfigError::EmptyCharacterSet { name }))
+                if name == charset::DIGITS
+        ));
+    }
+
+    #[test]
+    fn test_generate_password_with_unsatisfiable_constraints() {
+        // "0" and "1" are neighbours, and both must appear twice
+        let config = PasswordConfig::new(4)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .with_digits(false)
+            .with_custom_charset("binary", "01")
+            .with_max_sequence(1)
+            .with_max_occurrences(2);
+
+        assert!(matches!(
+            try_generate_with_rng(&config, &mut insecure_seeded_rng("unsatisfiable")),
+            Err(GenerateError::ConstraintsUnsatisfiable { attempts: MAX_ATTEMPTS })
+        ));
+    }
+
+    #[test]
+    fn test_password_generator_yields_errors() {
+        let config = PasswordConfig::new(4)
+            .unwrap()
+            .with_lowercase(false)
+            .with_uppercase(false)
+            .with_digits(false)
+            .with_custom_charset("binary", "01")
+            .with_max_sequence(1)
+            .with_max_occurrences(2);
+        let mut generator = PasswordGenerator::new(&config).unwrap();
+
+        assert!(matches!(
+            generator.next(),
+            Some(Err(GenerateError::ConstraintsUnsatisfiable { attempts: MAX_ATTEMPTS }))
+        ));
+        assert!(generator.next().is_none());
+        assert!(generator.next().is_none());
+    }
+
+    #[test]
+    fn test_password_generator() {
+        let config = PasswordConfig::new(12).unwrap().with_unique_chars(true);
+        let passwords: Vec<SecretString> = PasswordGenerator::new(&config)
+            .unwrap()
+            .with_reseed_interval(3)
+            .take(10)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(passwords.len(), 10);
+        for password in &passwords {
+            assert_eq!(password.expose_secret().len(), 12);
+            assert!(config.respects_constraints(password.expose_secret()));
+        }
+    }
+
+    #[test]
+    fn test_password_generator_with_invalid_config() {
+        let config = PasswordConfig::new(2).unwrap().with_math(true).with_braces(true);
+
+        assert!(matches!(
+            PasswordGenerator::new(&config),
+            Err(GenerateError::InvalidConfig(PasswordConfigError::LengthTooShortForSets { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_generate_password_with_seeded_rng() {
+        let config = PasswordConfig::new(24).unwrap().with_math(true);
+        let first = generate_password_with_rng(&config, &mut insecure_seeded_rng("seed")).unwrap();
+        let second = generate_password_with_rng(&config, &mut insecure_seeded_rng("seed")).unwrap();
+        let other = generate_password_with_rng(&config, &mut insecure_seeded_rng("other seed")).unwrap();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,438 @@
+//! Continuous health tests of the OS entropy source, in the style of NIST SP 800-90B §4.4.
+//!
+//! Every byte read from the OS goes through a repetition count test and an adaptive
+//! proportion test before seeding a backend RNG. Both assume 8 bits of entropy per
+//! byte, as promised by the OS, and use a false positive probability of 2^-40 per
+//! tested sample. The tests of the OS source are shared by the whole process, and once
+//! one fails, no RNG can be seeded anymore: the generators report
+//! `GenerateError::HealthTestFailed` instead of a password.
+//!
+//! Testing the output of a deterministic RNG would tell nothing about its seed, so
+//! only the `rng-os` backend, whose output is the OS source itself, also has its
+//! output tested by `HealthTestedRng`.
+//!
+//! `self_test` is the startup test: it checks that both tests reject stuck and biased
+//! sources, that the RNG algorithm of the backend matches its known answer, and runs
+//! the health tests on the first `STARTUP_SAMPLES` bytes of the OS source.
+//!
+//! The generators only run these tests when the `health-tests` feature is enabled.
+
+use crate::rng::{try_new_rng_from, BackendRng};
+use rand::rand_core::TryRngCore;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// A byte repeated this many times in a row fails the repetition count test
+pub const REPETITION_CUTOFF: usize = 6;
+
+/// Number of bytes in a window of the adaptive proportion test
+pub const ADAPTIVE_WINDOW: usize = 512;
+
+/// The first byte of a window seen this many times in the window fails the
+/// adaptive proportion test
+pub const ADAPTIVE_CUTOFF: usize = 20;
+
+/// Number of bytes tested at startup before the RNG is trusted
+pub const STARTUP_SAMPLES: usize = 1024;
+
+/// Error type for the RNG health tests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthError {
+    /// The same byte was read `run` times in a row
+    RepetitionCount { run: usize },
+    /// A byte was read `count` times in a window of `ADAPTIVE_WINDOW` bytes
+    AdaptiveProportion { count: usize },
+    /// The RNG algorithm did not produce its known answer
+    KnownAnswer,
+    /// The health tests accepted a broken source
+    SelfTest,
+    /// The OS entropy source could not seed the RNG
+    EntropyUnavailable,
+}
+
+impl core::fmt::Display for HealthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::RepetitionCount { run } => write!(
+                f,
+                "RNG health test failed: the same byte was repeated {} times in a row.",
+                run
+            ),
+            Self::AdaptiveProportion { count } => write!(
+                f,
+                "RNG health test failed: a byte was seen {} times in {} bytes.",
+                count, ADAPTIVE_WINDOW
+            ),
+            Self::KnownAnswer => write!(f, "RNG self-test failed: wrong known answer."),
+            Self::SelfTest => write!(f, "RNG self-test failed: a broken source was not detected."),
+            Self::EntropyUnavailable => {
+                write!(f, "RNG self-test failed: OS entropy source unavailable.")
+            }
+        }
+    }
+}
+
+impl core::error::Error for HealthError {}
+
+/// State of the repetition count and adaptive proportion tests
+#[derive(Debug, Clone, Default)]
+pub struct HealthTests {
+    last: Option<u8>,
+    run: usize,
+    window_first: u8,
+    window_len: usize,
+    window_count: usize,
+    failure: Option<HealthError>,
+}
+
+impl HealthTests {
+    /// Creates the tests, before any byte was seen
+    pub const fn new() -> Self {
+        Self {
+            last: None,
+            run: 0,
+            window_first: 0,
+            window_len: 0,
+            window_count: 0,
+            failure: None,
+        }
+    }
+
+    /// Tests the next byte. Once a test fails, every later call returns its error
+    pub fn feed(&mut self, byte: u8) -> Result<(), HealthError> {
+        if let Some(failure) = &self.failure {
+            return Err(failure.clone());
+        }
+
+        // repetition count test
+        if self.last == Some(byte) {
+            self.run += 1;
+        } else {
+            self.last = Some(byte);
+            self.run = 1;
+        }
+        if self.run >= REPETITION_CUTOFF {
+            return self.fail(HealthError::RepetitionCount { run: self.run });
+        }
+
+        // adaptive proportion test
+        if self.window_len == ADAPTIVE_WINDOW {
+            self.window_len = 0;
+        }
+        if self.window_len == 0 {
+            self.window_first = byte;
+            self.window_count = 0;
+        }
+        self.window_len += 1;
+        if byte == self.window_first {
+            self.window_count += 1;
+            if self.window_count >= ADAPTIVE_CUTOFF {
+                return self.fail(HealthError::AdaptiveProportion {
+                    count: self.window_count,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Tests every byte of `bytes`
+    pub fn feed_all(&mut self, bytes: &[u8]) -> Result<(), HealthError> {
+        bytes.iter().try_for_each(|byte| self.feed(*byte))
+    }
+
+    /// Returns the first failure, if any
+    pub fn check(&self) -> Result<(), HealthError> {
+        self.failure.clone().map_or(Ok(()), Err)
+    }
+
+    fn fail(&mut self, failure: HealthError) -> Result<(), HealthError> {
+        self.failure = Some(failure.clone());
+        Err(failure)
+    }
+}
+
+/// An entropy source whose every byte goes through the health tests, to seed an RNG.
+///
+/// Reading from the source fails once a test failed, so no RNG is seeded from a
+/// source which looks stuck or biased.
+#[derive(Debug, Clone)]
+pub struct HealthTestedSource<S> {
+    source: S,
+    tests: HealthTests,
+}
+
+impl<S> HealthTestedSource<S> {
+    /// Wraps `source`
+    pub const fn new(source: S) -> Self {
+        Self {
+            source,
+            tests: HealthTests::new(),
+        }
+    }
+}
+
+impl<S: TryRngCore> TryRngCore for HealthTestedSource<S> {
+    type Error = HealthError;
+
+    fn try_next_u32(&mut self) -> Result<u32, HealthError> {
+        let mut bytes = [0u8; 4];
+        self.try_fill_bytes(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, HealthError> {
+        let mut bytes = [0u8; 8];
+        self.try_fill_bytes(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn try_fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), HealthError> {
+        self.tests.check()?;
+        self.source
+            .try_fill_bytes(dst)
+            .map_err(|_| HealthError::EntropyUnavailable)?;
+        self.tests.feed_all(dst)
+    }
+}
+
+/// The OS entropy source, tested across every seed read by the process
+static OS_SOURCE: Mutex<HealthTestedSource<OsRng>> = Mutex::new(HealthTestedSource::new(OsRng));
+
+/// Reads `dst` from the health tested OS entropy source
+fn fill_from_os(dst: &mut [u8]) -> Result<(), HealthError> {
+    OS_SOURCE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .try_fill_bytes(dst)
+}
+
+/// A backend RNG seeded from the health tested OS entropy source.
+///
+/// With `rng-os`, every output byte is read from the OS, so it also goes through the
+/// health tests. `RngCore` can not report errors, so a failure is recorded and must
+/// be read with `check` before using the output.
+#[derive(Debug, Clone)]
+pub struct HealthTestedRng<R> {
+    rng: R,
+    tests: Option<HealthTests>,
+}
+
+impl<R: RngCore> HealthTestedRng<R> {
+    /// Wraps `rng`, testing its every output byte
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng,
+            tests: Some(HealthTests::new()),
+        }
+    }
+
+    /// Wraps `rng`, whose seed passed the health tests, without testing its output
+    pub fn seeded(rng: R) -> Self {
+        Self { rng, tests: None }
+    }
+
+    /// The wrapped RNG
+    pub fn get_ref(&self) -> &R {
+        &self.rng
+    }
+
+    /// Returns the first failure of the health tests, if any
+    pub fn check(&self) -> Result<(), HealthError> {
+        self.tests.as_ref().map_or(Ok(()), HealthTests::check)
+    }
+
+    fn feed(&mut self, bytes: &[u8]) {
+        if let Some(tests) = &mut self.tests {
+            let _ = tests.feed_all(bytes);
+        }
+    }
+}
+
+impl<R: RngCore> RngCore for HealthTestedRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        let value = self.rng.next_u32();
+        self.feed(&value.to_le_bytes());
+        value
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let value = self.rng.next_u64();
+        self.feed(&value.to_le_bytes());
+        value
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng.fill_bytes(dst);
+        self.feed(dst);
+    }
+}
+
+impl<R: CryptoRng> CryptoRng for HealthTestedRng<R> {}
+
+/// Known answer of the backend: the first two `u64` drawn with the seed 0, 1, ..., 31
+fn known_answer() -> Result<(), HealthError> {
+    #[cfg(all(feature = "rng-hc128", not(any(feature = "rng-chacha", feature = "rng-os"))))]
+    {
+        known_answer_of::<rand_hc::Hc128Rng>([0xca3bca5d1c72e02b, 0x89d9b814e5058921])
+    }
+    #[cfg(all(feature = "rng-chacha", not(feature = "rng-os")))]
+    {
+        known_answer_of::<rand_chacha::ChaCha20Rng>([0x6a19c5d97d2bfd39, 0x494adcb87703bd8d])
+    }
+    // the OS source has no known answer, only the startup samples are tested
+    #[cfg(feature = "rng-os")]
+    {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "rng-os"))]
+fn known_answer_of<R: rand::SeedableRng<Seed = [u8; 32]> + RngCore>(
+    expected: [u64; 2],
+) -> Result<(), HealthError> {
+    let mut seed = [0u8; 32];
+    for (i, byte) in seed.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let mut rng = R::from_seed(seed);
+    if [rng.next_u64(), rng.next_u64()] == expected {
+        Ok(())
+    } else {
+        Err(HealthError::KnownAnswer)
+    }
+}
+
+/// Checks that the health tests reject a stuck and a biased source
+fn detects_broken_sources() -> Result<(), HealthError> {
+    let stuck = [0x5a; REPETITION_CUTOFF];
+    if !matches!(
+        HealthTests::default().feed_all(&stuck),
+        Err(HealthError::RepetitionCount { .. })
+    ) {
+        return Err(HealthError::SelfTest);
+    }
+
+    // never twice in a row, but one byte out of two is 0
+    let biased: Vec<u8> = (0..ADAPTIVE_WINDOW)
+        .map(|i| if i % 2 == 0 { 0 } else { i as u8 | 1 })
+        .collect();
+    if !matches!(
+        HealthTests::default().feed_all(&biased),
+        Err(HealthError::AdaptiveProportion { .. })
+    ) {
+        return Err(HealthError::SelfTest);
+    }
+
+    Ok(())
+}
+
+/// Runs the startup self-test once and returns its result on every call
+pub fn self_test() -> Result<(), HealthError> {
+    static RESULT: OnceLock<Result<(), HealthError>> = OnceLock::new();
+
+    RESULT
+        .get_or_init(|| {
+            detects_broken_sources()?;
+            known_answer()?;
+
+            let mut samples = [0u8; STARTUP_SAMPLES];
+            fill_from_os(&mut samples)
+        })
+        .clone()
+}
+
+/// Creates a backend RNG seeded from the health tested OS entropy source, after the
+/// startup self-test passed
+pub fn new_health_tested_rng() -> Result<HealthTestedRng<BackendRng>, HealthError> {
+    self_test()?;
+    let rng = {
+        let mut source = OS_SOURCE.lock().unwrap_or_else(PoisonError::into_inner);
+        try_new_rng_from(&mut *source)?
+    };
+
+    #[cfg(feature = "rng-os")]
+    {
+        Ok(HealthTestedRng::new(rng))
+    }
+    #[cfg(not(feature = "rng-os"))]
+    {
+        Ok(HealthTestedRng::seeded(rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::insecure_seeded_rng;
+
+    /// An RNG returning the same byte forever
+    struct StuckRng(u8);
+
+    impl RngCore for StuckRng {
+        fn next_u32(&mut self) -> u32 {
+            u32::from_le_bytes([self.0; 4])
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            u64::from_le_bytes([self.0; 8])
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            dst.fill(self.0);
+        }
+    }
+
+    #[test]
+    fn test_self_test() {
+        assert_eq!(self_test(), Ok(()));
+    }
+
+    #[test]
+    fn test_stuck_rng_fails() {
+        let mut rng = HealthTestedRng::new(StuckRng(7));
+        rng.next_u64();
+
+        assert_eq!(rng.check(), Err(HealthError::RepetitionCount { run: REPETITION_CUTOFF }));
+    }
+
+    #[test]
+    fn test_stuck_source_seeds_nothing() {
+        // rng-os reads 4 bytes per seed, the other backends at least 32
+        let mut source = HealthTestedSource::new(StuckRng(7));
+
+        assert!((0..2).any(|_| try_new_rng_from(&mut source).is_err()));
+        assert_eq!(
+            source.try_next_u32(),
+            Err(HealthError::RepetitionCount { run: REPETITION_CUTOFF })
+        );
+    }
+
+    #[test]
+    fn test_new_health_tested_rng() {
+        let mut rng = new_health_tested_rng().unwrap();
+        rng.next_u64();
+
+        assert_eq!(rng.check(), Ok(()));
+    }
+
+    #[test]
+    fn test_failure_is_sticky() {
+        let mut tests = HealthTests::default();
+        let failure = tests.feed_all(&[1; REPETITION_CUTOFF]);
+
+        assert!(failure.is_err());
+        assert_eq!(tests.feed(2), failure);
+        assert_eq!(tests.check(), failure);
+    }
+
+    #[test]
+    fn test_healthy_rng_passes() {
+        let mut rng = HealthTestedRng::new(insecure_seeded_rng("healthy"));
+        let mut bytes = vec![0u8; 1 << 20];
+        rng.fill_bytes(&mut bytes);
+
+        assert_eq!(rng.check(), Ok(()));
+    }
+}
@@ -1,15 +1,62 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod batch;
+pub mod charset;
 pub mod config;
 pub mod generator;
+#[cfg(feature = "std")]
+pub mod health;
+#[cfg(not(feature = "std"))]
+mod math;
+pub mod passphrase;
+pub mod pattern;
+pub mod pin;
+pub mod pronounceable;
+#[cfg(feature = "std")]
+pub mod rng;
+pub mod secret;
+pub mod strength;
 
-
+#[cfg(feature = "std")]
+pub use batch::{generate_batch, generate_batch_to, BatchOptions};
+pub use charset::{Charset, CharsetRegistry};
 pub use config::PasswordConfig;
-pub use generator::generate_password;
+pub use generator::{
+    generate_password_with_rng, insecure_seeded_rng, try_generate_with_rng, GenerateError,
+};
+#[cfg(feature = "std")]
+pub use generator::{generate_password, try_generate, PasswordGenerator};
+pub use pattern::{Pattern, PatternError};
+#[cfg(feature = "std")]
+pub use pattern::generate_from_pattern;
+#[cfg(feature = "std")]
+pub use rng::RNG_BACKEND;
+pub use secret::SecretString;
+pub use pin::{generate_pin_with_rng, PinConfig};
+#[cfg(feature = "std")]
+pub use pin::generate_pin;
+pub use pronounceable::{generate_pronounceable_with_rng, pronounceable_entropy_bits};
+#[cfg(feature = "std")]
+pub use pronounceable::generate_pronounceable;
+pub use passphrase::{generate_passphrase_with_rng, Capitalization, PassphraseConfig};
+#[cfg(feature = "std")]
+pub use passphrase::generate_passphrase;
+pub use strength::{estimate_entropy_bits, Strength};
 
 pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const fn getversion() -> &'static str {
     APP_VERSION
 }
 
+/// Returns the version and the RNG backend, e.g. `0.4.0 (rng: hc128)`
+#[cfg(feature = "std")]
+pub fn getinfo() -> String {
+    format!("{} (rng: {})", APP_VERSION, RNG_BACKEND)
+}
+
 /// Character sets for password generation
 pub struct CharacterSets {
     /// Lowercase letters: a-z
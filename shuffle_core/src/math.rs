@@ -0,0 +1,21 @@
+//! The `f64` functions of `std` missing from `core`, computed by `libm`
+
+pub(crate) trait FloatExt {
+    fn ln(self) -> f64;
+    fn log2(self) -> f64;
+    fn exp(self) -> f64;
+}
+
+impl FloatExt for f64 {
+    fn ln(self) -> f64 {
+        libm::log(self)
+    }
+
+    fn log2(self) -> f64 {
+        libm::log2(self)
+    }
+
+    fn exp(self) -> f64 {
+        libm::exp(self)
+    }
+}
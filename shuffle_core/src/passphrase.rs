@@ -0,0 +1,290 @@
+use alloc::collections::BTreeSet;
+use alloc::{string::{String, ToString}, vec::Vec};
+use crate::config::PasswordConfigError;
+use crate::secret::SecretString;
+#[cfg(feature = "std")]
+use crate::generator::{with_generator_rng, GenerateError};
+use crate::DEFAULT_CHARSETS;
+use rand::{seq::IndexedRandom, CryptoRng, Rng, RngCore};
+use zeroize::Zeroize;
+
+/// Built-in wordlist (BIP-39 english, 2048 words), one word per line
+pub const BUILTIN_WORDLIST: &str = include_str!("../wordlists/bip39_english.txt");
+
+/// Returns the words of the built-in wordlist
+pub fn builtin_words() -> Vec<&'static str> {
+    BUILTIN_WORDLIST.lines().filter(|w| !w.is_empty()).collect()
+}
+
+/// How the words of a passphrase are capitalized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Capitalization {
+    /// all words in lowercase
+    #[default]
+    Lower,
+    /// ALL WORDS IN UPPERCASE
+    Upper,
+    /// First Letter Of Each Word
+    Title,
+    /// Each word is randomly lowercase or Title case
+    Random,
+}
+
+/// Configuration for passphrase generation
+#[derive(Debug, Clone)]
+pub struct PassphraseConfig {
+    /// Number of words in the passphrase
+    pub words: usize,
+
+    /// Separator inserted between words
+    pub separator: String,
+
+    /// Capitalization of the words
+    pub capitalization: Capitalization,
+
+    /// Append a random digit (0-9) to one of the words
+    pub include_digit: bool,
+
+    /// Append a random symbol (#$%&@^`~) to one of the words
+    pub include_symbol: bool,
+
+    /// Custom wordlist. The built-in wordlist is used when `None`
+    pub wordlist: Option<Vec<String>>,
+}
+
+impl Default for PassphraseConfig {
+    /// Creates a default passphrase configuration:
+    /// - 6 words from the built-in wordlist
+    /// - Separated by `-`
+    /// - Lowercase, without digit or symbol
+    fn default() -> Self {
+        Self {
+            words: 6,
+            separator: String::from("-"),
+            capitalization: Capitalization::Lower,
+            include_digit: false,
+            include_symbol: false,
+            wordlist: None,
+        }
+    }
+}
+
+impl PassphraseConfig {
+    /// Creates a new passphrase configuration with the specified number of words
+    /// and default settings for other options
+    pub fn new(words: usize) -> Result<Self, PasswordConfigError> {
+        if words == 0 {
+            return Err(PasswordConfigError::ZeroWords);
+        }
+
+        Ok(Self {
+            words,
+            ..Self::default()
+        })
+    }
+
+    /// Builder method to set the separator
+    pub fn with_separator(mut self, separator: String) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Builder method to set the capitalization mode
+    pub const fn with_capitalization(mut self, capitalization: Capitalization) -> Self {
+        self.capitalization = capitalization;
+        self
+    }
+
+    /// Builder method to set whether to append a digit
+    pub const fn with_digit(mut self, include: bool) -> Self {
+        self.include_digit = include;
+        self
+    }
+
+    /// Builder method to set whether to append a symbol
+    pub const fn with_symbol(mut self, include: bool) -> Self {
+        self.include_symbol = include;
+        self
+    }
+
+    /// Builder method to use a custom wordlist. Blank entries and duplicates are
+    /// ignored: a repeated word would be picked more often than the others
+    pub fn with_wordlist(mut self, wordlist: Vec<String>) -> Self {
+        let mut seen = BTreeSet::new();
+        self.wordlist = Some(
+            wordlist
+                .into_iter()
+                .map(|w| w.trim().to_string())
+                .filter(|w| !w.is_empty() && seen.insert(w.clone()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Distinct words the passphrase is drawn from. `wordlist` is a public field,
+    /// so it is deduplicated here too
+    fn words(&self) -> Vec<&str> {
+        match &self.wordlist {
+            Some(list) => {
+                let mut seen = BTreeSet::new();
+                list.iter()
+                    .map(|w| w.trim())
+                    .filter(|w| !w.is_empty() && seen.insert(*w))
+                    .collect()
+            }
+            None => builtin_words(),
+        }
+    }
+
+    /// Validates the configuration
+    pub fn validate(&self) -> Result<(), PasswordConfigError> {
+        if self.words == 0 {
+            return Err(PasswordConfigError::ZeroWords);
+        }
+
+        if self.wordlist.is_some() && self.words().is_empty() {
+            return Err(PasswordConfigError::EmptyWordlist);
+        }
+
+        Ok(())
+    }
+}
+
+fn capitalize<R: Rng + ?Sized>(word: &str, capitalization: Capitalization, rng: &mut R) -> String {
+    let title = |w: &str| {
+        let mut chars = w.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    };
+
+    match capitalization {
+        Capitalization::Lower => word.to_lowercase(),
+        Capitalization::Upper => word.to_uppercase(),
+        Capitalization::Title => title(word),
+        Capitalization::Random => {
+            if rng.random_bool(0.5) {
+                title(word)
+            } else {
+                word.to_lowercase()
+            }
+        }
+    }
+}
+
+/// Generate a passphrase
+///
+/// # Examples
+/// ```
+/// # use shuffle_core::{generate_passphrase, PassphraseConfig};
+///
+///  let config = PassphraseConfig::new(5).unwrap();
+///  let passphrase = generate_passphrase(&config).unwrap();
+/// assert_eq!(passphrase.expose_secret().split('-').count(), 5);
+/// ```
+#[cfg(feature = "std")]
+pub fn generate_passphrase(config: &PassphraseConfig) -> Result<SecretString, GenerateError> {
+    with_generator_rng(|rng| Ok(generate_passphrase_with_rng(config, rng)?))
+}
+
+/// Generate a passphrase using the given RNG
+pub fn generate_passphrase_with_rng<R: CryptoRng + RngCore + ?Sized>(
+    config: &PassphraseConfig,
+    rng: &mut R,
+) -> Result<SecretString, PasswordConfigError> {
+    config.validate()?;
+
+    let words = config.words();
+
+    let mut passphrase: Vec<String> = (0..config.words)
+        .map(|_| {
+            let word = words.choose(rng).unwrap_or(&"");
+            capitalize(word, config.capitalization, rng)
+        })
+        .collect();
+
+    if config.include_digit {
+        let digits: Vec<char> = DEFAULT_CHARSETS.digits.chars().collect();
+        let index = rng.random_range(0..passphrase.len());
+        passphrase[index].push(digits[rng.random_range(0..digits.len())]);
+    }
+
+    if config.include_symbol {
+        let symbols: Vec<char> = DEFAULT_CHARSETS.logograms.chars().collect();
+        let index = rng.random_range(0..passphrase.len());
+        passphrase[index].push(symbols[rng.random_range(0..symbols.len())]);
+    }
+
+    let secret = SecretString::from(passphrase.join(&config.separator));
+    passphrase.zeroize();
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_wordlist() {
+        let words = builtin_words();
+
+        assert_eq!(words.len(), 2048);
+    }
+
+    #[test]
+    fn test_generate_passphrase() {
+        let config = PassphraseConfig::new(4).unwrap().with_separator(" ".to_string());
+        let secret = generate_passphrase(&config).unwrap();        let passphrase = secret.expose_secret();
+        let words = builtin_words();
+
+        assert_eq!(passphrase.split(' ').count(), 4);
+        assert!(passphrase.split(' ').all(|w| words.contains(&w)));
+    }
+
+    #[test]
+    fn test_generate_passphrase_with_custom_wordlist() {
+        let config = PassphraseConfig::new(3)
+            .unwrap()
+            .with_capitalization(Capitalization::Upper)
+            .with_wordlist(vec!["alpha".to_string(), " ".to_string()]);
+        let secret = generate_passphrase(&config).unwrap();        let passphrase = secret.expose_secret();
+
+        assert_eq!(passphrase, "ALPHA-ALPHA-ALPHA");
+    }
+
+    #[test]
+    fn test_generate_passphrase_with_digit_and_symbol() {
+        let config = PassphraseConfig::new(3)
+            .unwrap()
+            .with_digit(true)
+            .with_symbol(true);
+        let secret = generate_passphrase(&config).unwrap();        let passphrase = secret.expose_secret();
+
+        assert!(passphrase.chars().any(|c| c.is_ascii_digit()));
+        assert!(passphrase.chars().any(|c| DEFAULT_CHARSETS.logograms.contains(c)));
+    }
+
+    #[test]
+    fn test_duplicated_words() {
+        let wordlist = ["alpha", "beta", "alpha", " beta", "alpha"].map(String::from);
+        let config = PassphraseConfig::new(3).unwrap().with_wordlist(wordlist.to_vec());
+        assert_eq!(config.wordlist.as_deref(), Some(&["alpha".to_string(), "beta".to_string()][..]));
+
+        let config = PassphraseConfig {
+            wordlist: Some(wordlist.to_vec()),
+            ..config
+        };
+        assert_eq!(config.words(), ["alpha", "beta"]);
+    }
+
+    #[test]
+    fn test_empty_wordlist() {
+        let config = PassphraseConfig::new(3).unwrap().with_wordlist(vec![]);
+
+        assert!(matches!(
+            generate_passphrase(&config),
+            Err(GenerateError::InvalidConfig(PasswordConfigError::EmptyWordlist))
+        ));
+    }
+}
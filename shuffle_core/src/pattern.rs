@@ -0,0 +1,320 @@
+use alloc::{string::{String, ToString}, vec::Vec};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatExt;
+#[cfg(feature = "std")]
+use crate::generator::{with_generator_rng, GenerateError};
+use crate::secret::SecretString;
+use crate::DEFAULT_CHARSETS;
+use rand::{CryptoRng, Rng, RngCore};
+
+/// Maximum number of characters a repetition `{n}` can extend a pattern to, so that
+/// `d{1000000000}` is rejected instead of exhausting memory
+pub const MAX_PATTERN_LENGTH: usize = 1024;
+
+/// Error type for pattern parsing
+#[derive(Debug, PartialEq, Eq)]
+pub enum PatternError {
+    EmptyPattern,
+    UnknownToken { position: usize, token: char },
+    UnclosedClass { position: usize },
+    EmptyClass { position: usize },
+    InvalidRepeat { position: usize },
+    TrailingEscape { position: usize },
+}
+
+impl core::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::EmptyPattern => write!(f, "Pattern must not be empty."),
+            Self::UnknownToken { position, token } => {
+                write!(f, "Unknown token '{}' at position {}.", token, position)
+            }
+            Self::UnclosedClass { position } => {
+                write!(f, "Custom class opened at position {} is never closed.", position)
+            }
+            Self::EmptyClass { position } => write!(
+                f,
+                "Token at position {} has no character left after exclusion.",
+                position
+            ),
+            Self::InvalidRepeat { position } => {
+                write!(f, "Invalid repetition at position {}.", position)
+            }
+            Self::TrailingEscape { position } => {
+                write!(f, "Escape at position {} is not followed by a character.", position)
+            }
+        }
+    }
+}
+
+impl core::error::Error for PatternError {}
+
+/// A single position of a pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternToken {
+    /// Always this character
+    Literal(char),
+    /// One character drawn uniformly from this class
+    Class(Vec<char>),
+}
+
+/// A parsed password template.
+///
+/// | Token    | Characters                                    |
+/// |----------|-----------------------------------------------|
+/// | `l`      | lowercase letters: a-z                        |
+/// | `U`      | uppercase letters: A-Z                        |
+/// | `a`      | lowercase and uppercase letters               |
+/// | `d`      | digits: 0-9                                   |
+/// | `b`      | braces: ()[]{}                                |
+/// | `p`      | punctuation: .,:;                             |
+/// | `q`      | quotes: "'                                    |
+/// | `h`      | dashes: -/\_\|                                |
+/// | `m`      | math: !*+<=>?                                 |
+/// | `g`      | logograms: #$%&@^`~                           |
+/// | `s`      | any symbol (braces to logograms)              |
+/// | `x`      | any character of all the classes above        |
+/// | `[...]`  | custom class, e.g. `[abc]`                    |
+/// | `\c`     | the literal character `c`                     |
+/// | `{n}`    | repeat the previous token `n` times, `n >= 1` |
+///
+/// Any other non alphanumeric character is a literal, so `Ulllldddd-s{4}` is
+/// one uppercase letter, four lowercase letters, four digits, a `-` and four symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub tokens: Vec<PatternToken>,
+}
+
+fn class_for(token: char) -> Option<String> {
+    let symbols = || {
+        [
+            DEFAULT_CHARSETS.braces,
+            DEFAULT_CHARSETS.punctuation,
+            DEFAULT_CHARSETS.quotes,
+            DEFAULT_CHARSETS.dashes,
+            DEFAULT_CHARSETS.math,
+            DEFAULT_CHARSETS.logograms,
+        ]
+        .concat()
+    };
+
+    let class = match token {
+        'l' => DEFAULT_CHARSETS.lowercase.to_string(),
+        'U' => DEFAULT_CHARSETS.uppercase.to_string(),
+        'a' => [DEFAULT_CHARSETS.lowercase, DEFAULT_CHARSETS.uppercase].concat(),
+        'd' => DEFAULT_CHARSETS.digits.to_string(),
+        'b' => DEFAULT_CHARSETS.braces.to_string(),
+        'p' => DEFAULT_CHARSETS.punctuation.to_string(),
+        'q' => DEFAULT_CHARSETS.quotes.to_string(),
+        'h' => DEFAULT_CHARSETS.dashes.to_string(),
+        'm' => DEFAULT_CHARSETS.math.to_string(),
+        'g' => DEFAULT_CHARSETS.logograms.to_string(),
+        's' => symbols(),
+        'x' => [
+            DEFAULT_CHARSETS.lowercase,
+            DEFAULT_CHARSETS.uppercase,
+            DEFAULT_CHARSETS.digits,
+            &symbols(),
+        ]
+        .concat(),
+        _ => return None,
+    };
+    Some(class)
+}
+
+impl Pattern {
+    /// Parses a pattern. Characters of `excluded` are removed from every class
+    /// (literals are kept as written).
+    pub fn parse(pattern: &str, excluded: &str) -> Result<Self, PatternError> {
+        if pattern.is_empty() {
+            return Err(PatternError::EmptyPattern);
+        }
+
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut tokens: Vec<PatternToken> = Vec::new();
+        let mut i = 0;
+
+        let filter = |class: &str, position: usize| {
+            let mut filtered: Vec<char> = Vec::new();
+            for c in class.chars().filter(|c| !excluded.contains(*c)) {
+                if !filtered.contains(&c) {
+                    filtered.push(c);
+                }
+            }
+            if filtered.is_empty() {
+                return Err(PatternError::EmptyClass { position });
+            }
+            Ok(PatternToken::Class(filtered))
+        };
+
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                '\\' => {
+                    let literal = *chars
+                        .get(i + 1)
+                        .ok_or(PatternError::TrailingEscape { position: i })?;
+                    tokens.push(PatternToken::Literal(literal));
+                    i += 2;
+                }
+                '[' => {
+                    let end = chars[i + 1..]
+                        .iter()
+                        .position(|c| *c == ']')
+                        .ok_or(PatternError::UnclosedClass { position: i })?;
+                    let class: String = chars[i + 1..i + 1 + end].iter().collect();
+                    tokens.push(filter(&class, i)?);
+                    i += end + 2;
+                }
+                '{' => {
+                    let end = chars[i + 1..]
+                        .iter()
+                        .position(|c| *c == '}')
+                        .ok_or(PatternError::InvalidRepeat { position: i })?;
+                    let count: usize = chars[i + 1..i + 1 + end]
+                        .iter()
+                        .collect::<String>()
+                        .parse()
+                        .map_err(|_| PatternError::InvalidRepeat { position: i })?;
+                    let previous = tokens
+                        .last()
+                        .cloned()
+                        .ok_or(PatternError::InvalidRepeat { position: i })?;
+                    if count == 0 || count - 1 > MAX_PATTERN_LENGTH.saturating_sub(tokens.len()) {
+                        return Err(PatternError::InvalidRepeat { position: i });
+                    }
+                    tokens.extend(core::iter::repeat_n(previous, count - 1));
+                    i += end + 2;
+                }
+                ']' | '}' => return Err(PatternError::UnknownToken { position: i, token: c }),
+                c if c.is_alphanumeric() => {
+                    let class = class_for(c)
+                        .ok_or(PatternError::UnknownToken { position: i, token: c })?;
+                    tokens.push(filter(&class, i)?);
+                    i += 1;
+                }
+                c => {
+                    tokens.push(PatternToken::Literal(c));
+                    i += 1;
+                }
+            }
+        }
+
+        Ok(Self { tokens })
+    }
+
+    /// Returns the entropy in bits of the passwords generated from this pattern
+    pub fn entropy_bits(&self) -> f64 {
+        self.tokens
+            .iter()
+            .map(|token| match token {
+                PatternToken::Literal(_) => 0.0,
+                PatternToken::Class(class) => (class.len() as f64).log2(),
+            })
+            .sum()
+    }
+
+    /// Generate a password from this pattern using the given RNG
+    pub fn generate_with_rng<R: CryptoRng + RngCore + ?Sized>(&self, rng: &mut R) -> SecretString {
+        let password: String = self.tokens
+            .iter()
+            .map(|token| match token {
+                PatternToken::Literal(c) => *c,
+                PatternToken::Class(class) => class[rng.random_range(0..class.len())],
+            })
+            .collect();
+        SecretString::from(password)
+    }
+}
+
+/// Generate a password from a pattern, see [`Pattern`] for the syntax
+///
+/// # Examples
+/// ```
+/// # use shuffle_core::generate_from_pattern;
+///
+///  let password = generate_from_pattern("Ulllldddd-s{4}", "").unwrap();
+/// assert_eq!(password.expose_secret().chars().count(), 14);
+/// assert_eq!(password.expose_secret().chars().nth(9), Some('-'));
+/// ```
+#[cfg(feature = "std")]
+pub fn generate_from_pattern(pattern: &str, excluded: &str) -> Result<SecretString, GenerateError> {
+    let pattern = Pattern::parse(pattern, excluded)?;
+    with_generator_rng(|rng| Ok(pattern.generate_with_rng(rng)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_from_pattern() {
+        let secret = generate_from_pattern("Ulllldddd-s{4}", "").unwrap();        let password = secret.expose_secret();
+        let chars: Vec<char> = password.chars().collect();
+
+        assert_eq!(chars.len(), 14);
+        assert!(chars[0].is_ascii_uppercase());
+        assert!(chars[1..5].iter().all(|c| c.is_ascii_lowercase()));
+        assert!(chars[5..9].iter().all(|c| c.is_ascii_digit()));
+        assert_eq!(chars[9], '-');
+        assert!(chars[10..].iter().all(|c| !c.is_alphanumeric()));
+    }
+
+    #[test]
+    fn test_pattern_with_excluded_and_custom_class() {
+        let secret = generate_from_pattern("d{10}[xyz]\\d", "012345678y").unwrap();        let password = secret.expose_secret();
+
+        assert_eq!(&password[..10], "9999999999");
+        assert!(password[10..11] == *"x" || password[10..11] == *"z");
+        assert_eq!(&password[11..], "d");
+    }
+
+    #[test]
+    fn test_pattern_entropy() {
+        let pattern = Pattern::parse("dd-[ab]", "").unwrap();
+
+        assert!((pattern.entropy_bits() - (2.0 * 10f64.log2() + 1.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pattern_errors() {
+        assert_eq!(Pattern::parse("", ""), Err(PatternError::EmptyPattern));
+        assert_eq!(
+            Pattern::parse("lz", ""),
+            Err(PatternError::UnknownToken { position: 1, token: 'z' })
+        );
+        assert_eq!(
+            Pattern::parse("l[ab", ""),
+            Err(PatternError::UnclosedClass { position: 1 })
+        );
+        assert_eq!(
+            Pattern::parse("q", "\"'"),
+            Err(PatternError::EmptyClass { position: 0 })
+        );
+        assert_eq!(
+            Pattern::parse("{3}", ""),
+            Err(PatternError::InvalidRepeat { position: 0 })
+        );
+        assert_eq!(
+            Pattern::parse("d{x}", ""),
+            Err(PatternError::InvalidRepeat { position: 1 })
+        );
+        assert_eq!(
+            Pattern::parse("d{0}", ""),
+            Err(PatternError::InvalidRepeat { position: 1 })
+        );
+        assert_eq!(
+            Pattern::parse("d{18446744073709551615}", ""),
+            Err(PatternError::InvalidRepeat { position: 1 })
+        );
+        assert_eq!(
+            Pattern::parse("d{1025}", ""),
+            Err(PatternError::InvalidRepeat { position: 1 })
+        );
+        assert_eq!(Pattern::parse("d{1024}", "").unwrap().tokens.len(), 1024);
+        assert_eq!(
+            Pattern::parse("d\\", ""),
+            Err(PatternError::TrailingEscape { position: 1 })
+        );
+    }
+}
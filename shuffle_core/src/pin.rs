@@ -0,0 +1,158 @@
+use alloc::{string::String, vec::Vec};
+use crate::config::PasswordConfigError;
+use crate::secret::SecretString;
+#[cfg(feature = "std")]
+use crate::generator::{with_generator_rng, GenerateError};
+use crate::DEFAULT_CHARSETS;
+use rand::{CryptoRng, Rng, RngCore};
+
+/// Minimum length of a PIN
+pub const MIN_PIN_LENGTH: usize = 4;
+
+/// Most common PINs, which are always tried first by an attacker
+pub const COMMON_PINS: [&str; 20] = [
+    "1234", "1111", "0000", "1212", "7777", "1004", "2000", "4444", "2222", "6969",
+    "9999", "3333", "5555", "6666", "1122", "1313", "8888", "4321", "2001", "1010",
+];
+
+/// Configuration for PIN generation
+#[derive(Debug, Clone)]
+pub struct PinConfig {
+    /// Number of digits of the PIN
+    pub length: usize,
+
+    /// Reject the PINs of `COMMON_PINS`
+    pub use_blocklist: bool,
+}
+
+impl Default for PinConfig {
+    /// Creates a default PIN configuration:
+    /// - 4 digits long
+    /// - Rejects the most common PINs
+    fn default() -> Self {
+        Self {
+            length: MIN_PIN_LENGTH,
+            use_blocklist: true,
+        }
+    }
+}
+
+impl PinConfig {
+    /// Creates a new PIN configuration with the specified length
+    /// and default settings for other options
+    pub fn new(length: usize) -> Result<Self, PasswordConfigError> {
+        if length < MIN_PIN_LENGTH {
+            return Err(PasswordConfigError::PinLengthTooShort);
+        }
+
+        Ok(Self {
+            length,
+            ..Self::default()
+        })
+    }
+
+    /// Builder method to set whether to reject the most common PINs
+    pub const fn with_blocklist(mut self, use_blocklist: bool) -> Self {
+        self.use_blocklist = use_blocklist;
+        self
+    }
+
+    /// Validates the configuration
+    pub const fn validate(&self) -> Result<(), PasswordConfigError> {
+        if self.length < MIN_PIN_LENGTH {
+            return Err(PasswordConfigError::PinLengthTooShort);
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if `pin` would never be produced with this configuration:
+    /// all digits identical, an ascending or descending run (1234, 9876),
+    /// or one of the most common PINs when the blocklist is enabled
+    pub fn is_weak(&self, pin: &str) -> bool {
+        let digits: Vec<i8> = pin
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|d| d as i8)
+            .collect();
+
+        let steps: Vec<i8> = digits.windows(2).map(|w| w[1] - w[0]).collect();
+        let constant_step = |step: i8| steps.iter().all(|s| *s == step);
+
+        constant_step(0)
+            || constant_step(1)
+            || constant_step(-1)
+            || (self.use_blocklist && COMMON_PINS.contains(&pin))
+    }
+}
+
+/// Generate a PIN
+///
+/// # Examples
+/// ```
+/// # use shuffle_core::{generate_pin, PinConfig};
+///
+///  let config = PinConfig::new(6).unwrap();
+///  let pin = generate_pin(&config).unwrap();
+/// assert_eq!(pin.expose_secret().len(), 6);
+/// ```
+#[cfg(feature = "std")]
+pub fn generate_pin(config: &PinConfig) -> Result<SecretString, GenerateError> {
+    with_generator_rng(|rng| Ok(generate_pin_with_rng(config, rng)?))
+}
+
+/// Generate a PIN using the given RNG
+pub fn generate_pin_with_rng<R: CryptoRng + RngCore + ?Sized>(
+    config: &PinConfig,
+    rng: &mut R,
+) -> Result<SecretString, PasswordConfigError> {
+    config.validate()?;
+
+    let digits: Vec<char> = DEFAULT_CHARSETS.digits.chars().collect();
+
+    // Weak PINs are a tiny fraction of all PINs, so rejection sampling ends quickly
+    // and keeps the remaining PINs uniformly distributed
+    loop {
+        let pin: String = (0..config.length)
+            .map(|_| digits[rng.random_range(0..digits.len())])
+            .collect();
+
+        if !config.is_weak(&pin) {
+            return Ok(SecretString::from(pin));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_pin() {
+        let config = PinConfig::new(8).unwrap();
+        let secret = generate_pin(&config).unwrap();        let pin = secret.expose_secret();
+
+        assert_eq!(pin.len(), 8);
+        assert!(pin.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn test_pin_too_short() {
+        assert!(matches!(
+            PinConfig::new(3),
+            Err(PasswordConfigError::PinLengthTooShort)
+        ));
+    }
+
+    #[test]
+    fn test_weak_pins() {
+        let config = PinConfig::default();
+
+        assert!(config.is_weak("0000"));
+        assert!(config.is_weak("3456"));
+        assert!(config.is_weak("9876"));
+        assert!(config.is_weak("1212"));
+        assert!(!config.is_weak("2580"));
+        assert!(!config.clone().with_blocklist(false).is_weak("1212"));
+    }
+}
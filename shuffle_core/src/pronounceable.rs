@@ -0,0 +1,296 @@
+use alloc::{string::String, vec::Vec};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatExt;
+use crate::charset::{DIGITS, LOWERCASE, SYMBOL_CHARSETS, UPPERCASE};
+use crate::config::{PasswordConfig, PasswordConfigError};
+use crate::secret::SecretString;
+#[cfg(feature = "std")]
+use crate::generator::{with_generator_rng, GenerateError};
+use crate::DEFAULT_CHARSETS;
+use rand::{CryptoRng, Rng, RngCore};
+use zeroize::Zeroize;
+
+/// Consonants used to build syllables
+pub const CONSONANTS: &str = "bcdfghjklmnprstvwxz";
+
+/// Vowels used to build syllables
+pub const VOWELS: &str = "aeiou";
+
+/// Alphabets of a pronounceable password, after removing the excluded characters
+struct Plan {
+    consonants: Vec<char>,
+    vowels: Vec<char>,
+    capital_consonants: Vec<char>,
+    capital_vowels: Vec<char>,
+    digits: Vec<char>,
+    symbols: Vec<char>,
+    letters: usize,
+    capital: bool,
+    uppercase: bool,
+}
+
+impl Plan {
+    fn new(config: &PasswordConfig) -> Result<Self, PasswordConfigError> {
+        let filter = |set: &str| -> Vec<char> {
+            set.chars().filter(|c| !config.excluded.contains(*c)).collect()
+        };
+        let lowercase = config.charsets.is_enabled(LOWERCASE);
+        let uppercase = config.charsets.is_enabled(UPPERCASE);
+        // the excluded characters are given in their displayed case
+        let filter_letters = |set: &[char], upper: bool| -> Vec<char> {
+            set.iter()
+                .copied()
+                .filter(|c| {
+                    let shown = if upper { c.to_ascii_uppercase() } else { *c };
+                    !config.excluded.contains(shown)
+                })
+                .collect()
+        };
+
+        let all_consonants: Vec<char> = CONSONANTS.chars().collect();
+        let all_vowels: Vec<char> = VOWELS.chars().collect();
+        let consonants = filter_letters(&all_consonants, !lowercase);
+        let vowels = filter_letters(&all_vowels, !lowercase);
+        if consonants.is_empty() || vowels.is_empty() {
+            return Err(PasswordConfigError::NotEnoughAvailableCharacters {
+                length: config.length,
+                available: consonants.len() + vowels.len(),
+            });
+        }
+
+        // with both cases, the capitalized letter is drawn from the letters whose
+        // uppercase is not excluded
+        let capital = uppercase && lowercase;
+        let (capital_consonants, capital_vowels) = if capital {
+            (filter_letters(&consonants, true), filter_letters(&vowels, true))
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        if capital && capital_consonants.is_empty() && capital_vowels.is_empty() {
+            return Err(PasswordConfigError::NotEnoughAvailableCharacters {
+                length: config.length,
+                available: 0,
+            });
+        }
+
+        let digits = if config.charsets.is_enabled(DIGITS) {
+            filter(DEFAULT_CHARSETS.digits)
+        } else {
+            Vec::new()
+        };
+
+        let symbols: Vec<char> = config
+            .charsets
+            .enabled()
+            .filter(|set| SYMBOL_CHARSETS.contains(&set.name.as_str()))
+            .flat_map(|set| filter(&set.chars))
+            .collect();
+
+        let extras = !digits.is_empty() as usize + !symbols.is_empty() as usize;
+        if config.length <= extras {
+            return Err(PasswordConfigError::LengthTooShortForSets {
+                length: config.length,
+                sets_count: extras + 1,
+            });
+        }
+
+        Ok(Self {
+            consonants,
+            vowels,
+            digits,
+            symbols,
+            capital_consonants,
+            capital_vowels,
+            letters: config.length - extras,
+            capital,
+            uppercase: uppercase && !lowercase,
+        })
+    }
+
+    /// Letters allowed at `index`, and those of them that can be capitalized
+    fn sets(&self, index: usize) -> (&[char], &[char]) {
+        if index.is_multiple_of(2) {
+            (&self.consonants, &self.capital_consonants)
+        } else {
+            (&self.vowels, &self.capital_vowels)
+        }
+    }
+
+    /// Positions where the capitalized letter can be
+    fn capital_positions(&self) -> Vec<usize> {
+        (0..self.letters).filter(|i| !self.sets(*i).1.is_empty()).collect()
+    }
+
+    fn entropy_bits(&self) -> f64 {
+        let consonants = self.letters.div_ceil(2) as f64;
+        let vowels = (self.letters / 2) as f64;
+        let mut bits = consonants * (self.consonants.len() as f64).log2()
+            + vowels * (self.vowels.len() as f64).log2();
+
+        // digits and symbols never collide with letters, so their value and
+        // their position can both be read back from the password
+        let mut positions = self.letters;
+        if self.capital {
+            // uniform position among the capitalizable ones, then a letter of the
+            // capitalizable subset at that position
+            let candidates = self.capital_positions();
+            let loss: f64 = candidates
+                .iter()
+                .map(|i| {
+                    let (set, capitals) = self.sets(*i);
+                    (capitals.len() as f64).log2() - (set.len() as f64).log2()
+                })
+                .sum();
+            bits += (candidates.len() as f64).log2() + loss / candidates.len() as f64;
+        }
+        if !self.digits.is_empty() {
+            positions += 1;
+            bits += (self.digits.len() as f64).log2() + (positions as f64).log2();
+        }
+        if !self.symbols.is_empty() {
+            positions += 1;
+            bits += (self.symbols.len() as f64).log2() + (positions as f64).log2();
+        }
+        bits
+    }
+}
+
+/// Returns the entropy in bits of the passwords produced by `generate_pronounceable`.
+///
+/// Syllables are far more predictable than random characters, so this is much lower
+/// than `PasswordConfig::entropy_bits` for the same length.
+pub fn pronounceable_entropy_bits(config: &PasswordConfig) -> Result<f64, PasswordConfigError> {
+    Ok(Plan::new(config)?.entropy_bits())
+}
+
+/// Generate a pronounceable password
+///
+/// Letters alternate between consonants and vowels. Depending on `config`, one
+/// letter is capitalized (or all of them when lowercase is disabled), and one digit
+/// and one symbol from the enabled symbol groups are inserted at random positions.
+///
+/// # Examples
+/// ```
+/// # use shuffle_core::{generate_pronounceable, PasswordConfig};
+///
+///  let config = PasswordConfig::new(12).unwrap();
+///  let password = generate_pronounceable(&config).unwrap();
+/// assert_eq!(password.expose_secret().len(), 12);
+/// ```
+#[cfg(feature = "std")]
+pub fn generate_pronounceable(config: &PasswordConfig) -> Result<SecretString, GenerateError> {
+    with_generator_rng(|rng| Ok(generate_pronounceable_with_rng(config, rng)?))
+}
+
+/// Generate a pronounceable password using the given RNG
+pub fn generate_pronounceable_with_rng<R: CryptoRng + RngCore + ?Sized>(
+    config: &PasswordConfig,
+    rng: &mut R,
+) -> Result<SecretString, PasswordConfigError> {
+    let plan = Plan::new(config)?;
+
+    let mut password: Vec<char> = (0..plan.letters)
+        .map(|i| {
+            let set = plan.sets(i).0;
+            let c = set[rng.random_range(0..set.len())];
+            if plan.uppercase {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    if plan.capital {
+        let candidates = plan.capital_positions();
+        let index = candidates[rng.random_range(0..candidates.len())];
+        let capitals = plan.sets(index).1;
+        password[index] = capitals[rng.random_range(0..capitals.len())].to_ascii_uppercase();
+    }
+
+    if !plan.digits.is_empty() {
+        let digit = plan.digits[rng.random_range(0..plan.digits.len())];
+        password.insert(rng.random_range(0..=password.len()), digit);
+    }
+
+    if !plan.symbols.is_empty() {
+        let symbol = plan.symbols[rng.random_range(0..plan.symbols.len())];
+        password.insert(rng.random_range(0..=password.len()), symbol);
+    }
+
+    let secret = SecretString::from(password.iter().collect::<String>());
+    password.zeroize();
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_pronounceable() {
+        let config = PasswordConfig::new(10)
+            .unwrap()
+            .with_uppercase(false)
+            .with_digits(false);
+        let secret = generate_pronounceable(&config).unwrap();        let password = secret.expose_secret();
+
+        for (i, c) in password.chars().enumerate() {
+            let set = if i % 2 == 0 { CONSONANTS } else { VOWELS };
+            assert!(set.contains(c));
+        }
+        assert_eq!(password.len(), 10);
+    }
+
+    #[test]
+    fn test_generate_pronounceable_with_digit_and_symbol() {
+        let config = PasswordConfig::new(8).unwrap().with_math(true);
+        let secret = generate_pronounceable(&config).unwrap();        let password = secret.expose_secret();
+
+        assert_eq!(password.len(), 8);
+        assert_eq!(password.chars().filter(|c| c.is_ascii_digit()).count(), 1);
+        assert_eq!(password.chars().filter(|c| c.is_ascii_uppercase()).count(), 1);
+        assert_eq!(password.chars().filter(|c| "!*+<=>?".contains(*c)).count(), 1);
+    }
+
+    #[test]
+    fn test_pronounceable_excluded_capital() {
+        let config = PasswordConfig::new(12).unwrap().excluded("AEIOUY".to_string());
+
+        for _ in 0..200 {
+            let secret = generate_pronounceable(&config).unwrap();            let password = secret.expose_secret();
+            assert!(!password.contains(|c| "AEIOUY".contains(c)), "{}", password);
+            assert_eq!(password.chars().filter(|c| c.is_ascii_uppercase()).count(), 1);
+        }
+
+        // the capital is at one of the 6 consonant positions of the 12 letters
+        let config = config.with_digits(false);
+        let bits = pronounceable_entropy_bits(&config).unwrap();
+        assert!((bits - (6.0 * 19f64.log2() + 6.0 * 5f64.log2() + 6f64.log2())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pronounceable_entropy() {
+        let config = PasswordConfig::new(4)
+            .unwrap()
+            .with_uppercase(false)
+            .with_digits(false);
+        let bits = pronounceable_entropy_bits(&config).unwrap();
+
+        assert!((bits - 2.0 * (19f64.log2() + 5f64.log2())).abs() < 1e-9);
+        assert!(bits < config.entropy_bits());
+    }
+
+    #[test]
+    fn test_pronounceable_too_short() {
+        let config = PasswordConfig::new(2).unwrap().with_logograms(true);
+
+        assert!(matches!(
+            generate_pronounceable(&config),
+            Err(GenerateError::InvalidConfig(PasswordConfigError::LengthTooShortForSets {
+                length: 2,
+                sets_count: 3
+            }))
+        ));
+    }
+}
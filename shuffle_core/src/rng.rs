@@ -0,0 +1,144 @@
+//! The RNG backend used by every generator, selected with a cargo feature.
+//!
+//! | Feature               | RNG                                                          |
+//! |-----------------------|--------------------------------------------------------------|
+//! | `rng-hc128` (default) | `Hc128Rng` seeded from an `Isaac64Rng` seeded from the OS    |
+//! | `rng-chacha`          | `ChaCha20Rng` seeded from the OS                             |
+//! | `rng-os`              | `OsBackendRng`: every byte is read from the OS (`getrandom`) |
+//!
+//! With `rng-hc128` and `rng-chacha`, every `generate_*` call seeds a new RNG from
+//! the OS, and a `PasswordGenerator` reseeds its RNG from the OS every
+//! `RESEED_INTERVAL` passwords. `rng-os` keeps no state, so it never needs reseeding;
+//! a `PasswordGenerator` still checks that the OS source answers at each interval.
+//! A later failure of the OS source does not panic: `OsBackendRng` keeps it, and the
+//! generators return it as `GenerateError::EntropyUnavailable` (see `check_rng`).
+//!
+//! When several backends are enabled, `rng-os` wins over `rng-chacha`, which wins
+//! over `rng-hc128`, so `--features rng-os` is enough to leave the default backend.
+
+use rand::rand_core::{OsError, TryRngCore};
+
+#[cfg(not(any(feature = "rng-hc128", feature = "rng-chacha", feature = "rng-os")))]
+compile_error!("shuffle_core needs one of the rng-hc128, rng-chacha or rng-os features");
+
+#[cfg(feature = "rng-os")]
+mod backend {
+    use rand::rand_core::{OsError, TryRngCore};
+    use rand::rngs::OsRng;
+    use rand::{CryptoRng, RngCore};
+
+    pub const NAME: &str = "os";
+
+    pub type BackendRng = OsBackendRng;
+
+    /// `OsRng` behind the infallible `RngCore`: the first failure of the OS source is
+    /// kept, and every byte drawn from then on is 0
+    #[derive(Debug, Default)]
+    pub struct OsBackendRng {
+        error: Option<OsError>,
+    }
+
+    impl RngCore for OsBackendRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_le_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            if self.error.is_none() {
+                self.error = OsRng.try_fill_bytes(dst).err();
+            }
+            if self.error.is_some() {
+                dst.fill(0);
+            }
+        }
+    }
+
+    impl CryptoRng for OsBackendRng {}
+
+    pub fn try_from_source<S: TryRngCore>(source: &mut S) -> Result<BackendRng, S::Error> {
+        // make sure the source answers, later failures are returned by `check`
+        source.try_next_u32()?;
+        Ok(OsBackendRng::default())
+    }
+
+    pub fn check(rng: &BackendRng) -> Result<(), OsError> {
+        rng.error.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(all(feature = "rng-chacha", not(feature = "rng-os")))]
+mod backend {
+    use rand::rand_core::TryRngCore;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    pub const NAME: &str = "chacha20";
+
+    pub type BackendRng = ChaCha20Rng;
+
+    pub fn try_from_source<S: TryRngCore>(source: &mut S) -> Result<BackendRng, S::Error> {
+        ChaCha20Rng::try_from_rng(source)
+    }
+
+    pub fn check(_: &BackendRng) -> Result<(), rand::rand_core::OsError> {
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "rng-hc128", not(any(feature = "rng-chacha", feature = "rng-os"))))]
+mod backend {
+    use rand::rand_core::TryRngCore;
+    use rand::SeedableRng;
+    use rand_hc::Hc128Rng;
+    use rand_isaac::Isaac64Rng;
+
+    pub const NAME: &str = "hc128";
+
+    pub type BackendRng = Hc128Rng;
+
+    pub fn try_from_source<S: TryRngCore>(source: &mut S) -> Result<BackendRng, S::Error> {
+        let mut isaac_seeder = Isaac64Rng::try_from_rng(source)?;
+        Ok(Hc128Rng::from_rng(&mut isaac_seeder))
+    }
+
+    pub fn check(_: &BackendRng) -> Result<(), rand::rand_core::OsError> {
+        Ok(())
+    }
+}
+
+/// Name of the RNG backend selected at build time: `hc128`, `chacha20` or `os`
+pub const RNG_BACKEND: &str = backend::NAME;
+
+/// The RNG type of the selected backend
+pub type BackendRng = backend::BackendRng;
+
+#[cfg(feature = "rng-os")]
+pub use backend::OsBackendRng;
+
+/// Create the RNG used by every generator, seeded from the OS entropy source. With
+/// `health-tests`, the RNG is seeded from the tested source of the `health` module
+#[cfg(not(feature = "health-tests"))]
+pub(crate) fn try_new_rng() -> Result<BackendRng, OsError> {
+    try_new_rng_from(&mut rand::rngs::OsRng)
+}
+
+/// Create the RNG used by every generator, seeded from `source`
+pub(crate) fn try_new_rng_from<S: TryRngCore>(
+    source: &mut S,
+) -> Result<BackendRng, S::Error> {
+    backend::try_from_source(source)
+}
+
+/// Returns the failure of the OS source met while drawing from `rng`, if any. Only
+/// `rng-os` draws from the OS after seeding, the other backends always return `Ok`
+pub(crate) fn check_rng(rng: &BackendRng) -> Result<(), OsError> {
+    backend::check(rng)
+}
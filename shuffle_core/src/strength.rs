@@ -0,0 +1,118 @@
+use crate::charset::CharsetRegistry;
+use crate::generator::graphemes;
+#[cfg(not(feature = "std"))]
+use crate::math::FloatExt;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+/// Strength rating of a password, from its estimated entropy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Strength {
+    /// Less than 28 bits
+    VeryWeak,
+    /// 28 to 35 bits
+    Weak,
+    /// 36 to 59 bits
+    Reasonable,
+    /// 60 to 127 bits
+    Strong,
+    /// 128 bits and more
+    VeryStrong,
+}
+
+impl Strength {
+    /// Rates an entropy in bits
+    pub fn from_bits(bits: f64) -> Self {
+        match bits {
+            b if b < 28.0 => Self::VeryWeak,
+            b if b < 36.0 => Self::Weak,
+            b if b < 60.0 => Self::Reasonable,
+            b if b < 128.0 => Self::Strong,
+            _ => Self::VeryStrong,
+        }
+    }
+}
+
+impl core::fmt::Display for Strength {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            Self::VeryWeak => "very weak",
+            Self::Weak => "weak",
+            Self::Reasonable => "reasonable",
+            Self::Strong => "strong",
+            Self::VeryStrong => "very strong",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Estimates the entropy in bits of an existing password.
+///
+/// The password is assumed drawn from every built-in character class it uses (plus
+/// its characters outside of them). A character repeating the previous one or
+/// continuing an ascending or descending run only counts for 1 bit. Words and
+/// common passwords are not detected: this is an upper bound, unlike
+/// `PasswordConfig::entropy_bits`, a lower bound (min-entropy) for generated passwords
+/// which is only an estimate when repetition, sequence or occurrence limits are set.
+pub fn estimate_entropy_bits(password: &str) -> f64 {
+    let chars: Vec<&str> = graphemes(password).collect();
+    let registry = CharsetRegistry::default();
+
+    let mut pool = 0;
+    let mut unclassified = BTreeSet::new();
+    for charset in registry.iter() {
+        let members: BTreeSet<&str> = graphemes(&charset.chars).collect();
+        if chars.iter().any(|c| members.contains(c)) {
+            pool += members.len();
+        }
+    }
+    for c in &chars {
+        if !registry.iter().any(|charset| charset.chars.contains(*c)) {
+            unclassified.insert(*c);
+        }
+    }
+    pool += unclassified.len();
+    if pool < 2 {
+        return 0.0;
+    }
+
+    let code = |c: &str| c.chars().next().map_or(0, |c| c as i64);
+    let bits_per_char = (pool as f64).log2();
+    let mut bits = 0.0;
+    let mut last_step = None;
+    for (i, c) in chars.iter().enumerate() {
+        let step = i.checked_sub(1).map(|p| code(c) - code(chars[p]));
+        let predictable =
+            step == Some(0) || (step.is_some_and(|s| s.abs() == 1) && step == last_step);
+        bits += if predictable { 1.0 } else { bits_per_char };
+        last_step = step;
+    }
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_entropy_bits() {
+        // 10 digits out of 10
+        assert!((estimate_entropy_bits("5820193746") - 10.0 * 10f64.log2()).abs() < 1e-9);
+        assert_eq!(estimate_entropy_bits(""), 0.0);
+        assert!(estimate_entropy_bits("aaaaaaaaaaaa") < estimate_entropy_bits("qmzvkrtwpxbh"));
+        assert!(estimate_entropy_bits("abcdefgh") < estimate_entropy_bits("ahcgdbfe"));
+    }
+
+    #[test]
+    fn test_strength() {
+        assert_eq!(
+            Strength::from_bits(estimate_entropy_bits("1234")),
+            Strength::VeryWeak
+        );
+        assert_eq!(
+            Strength::from_bits(estimate_entropy_bits("T4k!9qZ#mW2$xP7&")),
+            Strength::Strong
+        );
+        assert_eq!(Strength::VeryStrong.to_string(), "very strong");
+    }
+}
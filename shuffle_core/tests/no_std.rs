@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::process::Command;
+
+    /// Bare-metal target without `std`, overridden by `SHUFFLE_NO_STD_TARGET`
+    const NO_STD_TARGET: &str = "thumbv7em-none-eabihf";
+
+    fn target_installed(target: &str) -> bool {
+        Command::new("rustc")
+            .args(["--print", "target-libdir", "--target", target])
+            .output()
+            .map(|output| {
+                let libdir = String::from_utf8_lossy(&output.stdout);
+                output.status.success() && Path::new(libdir.trim()).exists()
+            })
+            .unwrap_or(false)
+    }
+
+    fn build_without_std(target: &str) {
+        let mut cmd = Command::new(env!("CARGO"));
+        cmd.args(["build", "--lib", "--no-default-features", "--manifest-path"])
+            .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("no_std"))
+            .args(["--target", target]);
+        if std::env::var_os("CARGO_NET_OFFLINE").is_some() {
+            cmd.arg("--offline");
+        }
+
+        let output = cmd.output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    /// A host build would link std for a dependency without failing, so the test
+    /// needs a target without std. CI installs it and runs the ignored tests
+    #[test]
+    #[ignore = "needs the thumbv7em-none-eabihf target, run with --ignored"]
+    fn test_build_without_std() {
+        let target = std::env::var("SHUFFLE_NO_STD_TARGET").unwrap_or(NO_STD_TARGET.to_string());
+
+        assert!(
+            target_installed(&target),
+            "{} is not installed, run `rustup target add {}`",
+            target,
+            target
+        );
+        build_without_std(&target);
+    }
+}
@@ -2,6 +2,7 @@
 
 use eframe::egui;
 use password_generator::ComplexPasswordGenerator;
+use shuffle_core::SecretString;
 mod password_generator;
 
 struct AppState {
@@ -11,10 +12,11 @@ struct AppState {
     include_numbers: bool,
     include_symbols: bool,
     num_passwords: usize,
-    generated_passwords: Vec<String>,
+    generated_passwords: Vec<SecretString>,
     password_generator: Box<ComplexPasswordGenerator>,
-    copied_password: Option<String>,
+    copied_password: Option<SecretString>,
     all_passwords_copied: bool,
+    error: Option<String>,
 }
 
 impl AppState {
@@ -30,16 +32,17 @@ impl AppState {
             password_generator: Box::new(ComplexPasswordGenerator::new(true, true, false, false)),
             copied_password: None,
             all_passwords_copied: false,
+            error: None,
         }
     }
 
     fn update_generator(&mut self) {
-        self.password_generator = Box::new(ComplexPasswordGenerator::new(
+        *self.password_generator = ComplexPasswordGenerator::new(
             self.include_uppercase,
             self.include_lowercase,
             self.include_numbers,
             self.include_symbols,
-        ));
+        );
     }
 
     fn is_generation_enabled(&self) -> bool {
@@ -149,9 +152,19 @@ impl eframe::App for AppState {
                         if ui.add_enabled(self.is_generation_enabled(), generate_passwords_button).clicked() {
                             self.all_passwords_copied = false;
                             self.update_generator();
-                            self.generated_passwords = (0..self.num_passwords)
+                            let passwords = (0..self.num_passwords)
                                 .map(|_| self.password_generator.generate_password(self.password_length))
-                                .collect();
+                                .collect::<Result<_, _>>();
+                            match passwords {
+                                Ok(passwords) => {
+                                    self.generated_passwords = passwords;
+                                    self.error = None;
+                                }
+                                Err(e) => {
+                                    self.generated_passwords.clear();
+                                    self.error = Some(e.to_string());
+                                }
+                            }
                         }
 
                         let copy_all_passwords_button = egui::Button::new(
@@ -163,8 +176,14 @@ impl eframe::App for AppState {
                         .corner_radius(6.0);
 
                         if ui.add_enabled(!self.generated_passwords.is_empty(), copy_all_passwords_button).clicked() {
-                            let all_passwords = self.generated_passwords.join("\n");
-                            ui.ctx().copy_text(all_passwords);
+                            let all_passwords = SecretString::from(
+                                self.generated_passwords
+                                    .iter()
+                                    .map(SecretString::expose_secret)
+                                    .collect::<Vec<_>>()
+                                    .join("\n"),
+                            );
+                            ui.ctx().copy_text(all_passwords.expose_secret().to_string());
                             self.all_passwords_copied = true;
                         }
             
@@ -179,6 +198,14 @@ impl eframe::App for AppState {
                 );
             });
 
+            if let Some(error) = &self.error {
+                ui.label(
+                    egui::RichText::new(format!("⚠ {}", error))
+                        .size(14.0)
+                        .color(egui::Color32::from_rgb(239, 68, 68))
+                );
+            }
+
             if !self.generated_passwords.is_empty() {
                 ui.separator();
                 egui::Frame::group(ui.style())
@@ -193,10 +220,10 @@ impl eframe::App for AppState {
                                         for password in &self.generated_passwords {
                                             ui.horizontal(|ui| {
                                                 ui.set_width(ui.available_width());
-                                                let response = ui.monospace(password);
+                                                let response = ui.monospace(password.expose_secret());
                                                 
                                                 if response.clicked() {
-                                                    ui.ctx().copy_text(password.clone());
+                                                    ui.ctx().copy_text(password.expose_secret().to_string());
                                                     self.copied_password = Some(password.clone());
                                                     self.all_passwords_copied = false;
                                                 }
@@ -17,14 +17,14 @@ impl ComplexPasswordGenerator {
             include_logograms,
         }
     }
-    pub fn generate_password(&self, length: usize) -> String {
+    pub fn generate_password(&self, length: usize) -> Result<SecretString, GenerateError> {
 
-        let config = PasswordConfig::new(length).unwrap()
+        let config = PasswordConfig::new(length)?
             .with_uppercase(self.include_uppercase)
             .with_lowercase(self.include_lowercase)
             .with_digits(self.include_numbers)
             .with_logograms(self.include_logograms);
 
-        generate_password(&config)
+        try_generate(&config)
     }
 }
\ No newline at end of file
@@ -0,0 +1,312 @@
+//! Python bindings of `shuffle_core`, built with PyO3.
+//!
+//! ```python
+//! import shuffle_py
+//!
+//! config = shuffle_py.PasswordConfig(20, include_braces=True, excluded="0O1Il5S")
+//! config.validate()                  # raises InvalidConfigError
+//! print(config.entropy_bits)
+//! print(shuffle_py.generate_password(config))
+//! print(shuffle_py.generate_batch(config, 1000, unique=True))
+//! ```
+//!
+//! Build and install the module in the current virtualenv with `maturin develop`.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use shuffle_core::config::PasswordConfigError;
+use shuffle_core::{charset, BatchOptions, GenerateError};
+
+create_exception!(
+    shuffle_py,
+    InvalidConfigError,
+    PyValueError,
+    "The password configuration is invalid."
+);
+create_exception!(
+    shuffle_py,
+    GenerationError,
+    PyRuntimeError,
+    "No password could be generated from a valid configuration."
+);
+
+fn config_error(e: PasswordConfigError) -> PyErr {
+    InvalidConfigError::new_err(e.to_string())
+}
+
+fn generate_error(e: GenerateError) -> PyErr {
+    match e {
+        GenerateError::InvalidConfig(e) => config_error(e),
+        e => GenerationError::new_err(e.to_string()),
+    }
+}
+
+/// Configuration for password generation, see `shuffle_core::PasswordConfig`
+#[pyclass(name = "PasswordConfig", module = "shuffle_py")]
+pub struct PyPasswordConfig(shuffle_core::PasswordConfig);
+
+#[pymethods]
+impl PyPasswordConfig {
+    /// Raises `InvalidConfigError` if `length` is 0. The other problems are only
+    /// reported by `validate` and the generators.
+    #[new]
+    #[pyo3(signature = (
+        length = 10,
+        *,
+        include_lowercase = true,
+        include_uppercase = true,
+        include_digits = true,
+        include_braces = false,
+        include_punctuation = false,
+        include_quotes = false,
+        include_dashes = false,
+        include_math = false,
+        include_logograms = false,
+        excluded = String::new(),
+        included = String::new(),
+        max_consecutive = None,
+        max_sequence = None,
+        max_occurrences = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        length: usize,
+        include_lowercase: bool,
+        include_uppercase: bool,
+        include_digits: bool,
+        include_braces: bool,
+        include_punctuation: bool,
+        include_quotes: bool,
+        include_dashes: bool,
+        include_math: bool,
+        include_logograms: bool,
+        excluded: String,
+        included: String,
+        max_consecutive: Option<usize>,
+        max_sequence: Option<usize>,
+        max_occurrences: Option<usize>,
+    ) -> PyResult<Self> {
+        let mut config = shuffle_core::PasswordConfig::new(length)
+            .map_err(config_error)?
+            .with_lowercase(include_lowercase)
+            .with_uppercase(include_uppercase)
+            .with_digits(include_digits)
+            .with_braces(include_braces)
+            .with_punctuation(include_punctuation)
+            .with_quotes(include_quotes)
+            .with_dashes(include_dashes)
+            .with_math(include_math)
+            .with_logograms(include_logograms)
+            .excluded(excluded)
+            .included(included);
+        config.max_consecutive = max_consecutive;
+        config.max_sequence = max_sequence;
+        config.max_occurrences = max_occurrences;
+
+        Ok(Self(config))
+    }
+
+    #[getter]
+    fn length(&self) -> usize {
+        self.0.length
+    }
+
+    #[setter]
+    fn set_length(&mut self, length: usize) {
+        self.0.length = length;
+    }
+
+    #[getter]
+    fn excluded(&self) -> String {
+        self.0.excluded.clone()
+    }
+
+    #[setter]
+    fn set_excluded(&mut self, excluded: String) {
+        self.0.excluded = excluded;
+    }
+
+    #[getter]
+    fn included(&self) -> String {
+        self.0.included.clone()
+    }
+
+    #[setter]
+    fn set_included(&mut self, included: String) {
+        self.0.included = included;
+    }
+
+    /// Entropy of a generated password in bits
+    #[getter]
+    fn entropy_bits(&self) -> f64 {
+        self.0.entropy_bits()
+    }
+
+    /// Whether a character class is enabled, e.g. `config.is_enabled("digits")`
+    fn is_enabled(&self, name: &str) -> bool {
+        self.0.charsets.is_enabled(name)
+    }
+
+    /// Enables or disables a character class by name, raises `InvalidConfigError` if
+    /// no class has this name
+    fn set_charset(&mut self, name: &str, include: bool) -> PyResult<()> {
+        if self.0.charsets.get(name).is_none() {
+            return Err(config_error(PasswordConfigError::UnknownCharacterSet {
+                name: name.to_string(),
+            }));
+        }
+        self.0.charsets.set_enabled(name, include);
+        Ok(())
+    }
+
+    /// Registers a custom character class and enables it
+    fn add_custom_charset(&mut self, name: &str, chars: &str) {
+        self.0.charsets.register(name, chars);
+        self.0.charsets.set_enabled(name, true);
+    }
+
+    /// Raises `InvalidConfigError` describing the problem if the configuration is invalid
+    fn validate(&self) -> PyResult<()> {
+        self.0.validate().map_err(config_error)
+    }
+
+    fn __repr__(&self) -> String {
+        let enabled: Vec<&str> = self.0.charsets.enabled().map(|c| c.name.as_str()).collect();
+        format!(
+            "PasswordConfig(length={}, charsets=[{}], excluded={:?}, included={:?})",
+            self.0.length,
+            enabled.join(", "),
+            self.0.excluded,
+            self.0.included
+        )
+    }
+}
+
+/// Generates a password.
+///
+/// Raises `InvalidConfigError` on an invalid configuration and `GenerationError`
+/// when the constraints can not be satisfied or the entropy source fails.
+#[pyfunction]
+fn generate_password(py: Python<'_>, config: &PyPasswordConfig) -> PyResult<String> {
+    let password = py
+        .detach(|| shuffle_core::try_generate(&config.0))
+        .map_err(generate_error)?;
+    Ok(password.expose_secret().to_string())
+}
+
+/// Generates `count` passwords across `threads` threads (all the cores when 0),
+/// all different when `unique` is set.
+#[pyfunction]
+#[pyo3(signature = (config, count, *, threads = 0, unique = false))]
+fn generate_batch(
+    py: Python<'_>,
+    config: &PyPasswordConfig,
+    count: usize,
+    threads: usize,
+    unique: bool,
+) -> PyResult<Vec<String>> {
+    let options = BatchOptions::default()
+        .with_threads(threads)
+        .with_unique(unique);
+    let passwords = py
+        .detach(|| shuffle_core::generate_batch(&config.0, count, options))
+        .map_err(generate_error)?;
+    Ok(passwords
+        .iter()
+        .map(|password| password.expose_secret().to_string())
+        .collect())
+}
+
+#[pymodule]
+fn shuffle_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPasswordConfig>()?;
+    m.add_function(wrap_pyfunction!(generate_password, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_batch, m)?)?;
+    m.add("InvalidConfigError", m.py().get_type::<InvalidConfigError>())?;
+    m.add("GenerationError", m.py().get_type::<GenerationError>())?;
+    m.add("__version__", shuffle_core::getversion())?;
+    for name in [
+        charset::LOWERCASE,
+        charset::UPPERCASE,
+        charset::DIGITS,
+        charset::BRACES,
+        charset::PUNCTUATION,
+        charset::QUOTES,
+        charset::DASHES,
+        charset::MATH,
+        charset::LOGOGRAMS,
+    ] {
+        m.add(name.to_uppercase(), name)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    fn run(code: &str) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "shuffle_py").unwrap();
+            shuffle_py(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("shuffle_py", module).unwrap();
+            let code = std::ffi::CString::new(code).unwrap();
+            py.run(&code, Some(&globals), None).unwrap();
+        });
+    }
+
+    #[test]
+    fn test_generate_password() {
+        run(r#"
+config = shuffle_py.PasswordConfig(24, include_uppercase=False, include_math=True, excluded="abc")
+password = shuffle_py.generate_password(config)
+assert len(password) == 24
+assert not any(c in "abcABC" for c in password)
+assert config.is_enabled(shuffle_py.MATH)
+"#);
+    }
+
+    #[test]
+    fn test_invalid_config_raises() {
+        run(r#"
+try:
+    shuffle_py.PasswordConfig(0)
+    assert False
+except shuffle_py.InvalidConfigError:
+    pass
+
+config = shuffle_py.PasswordConfig(12, include_lowercase=False, include_uppercase=False, include_digits=False)
+for check in (config.validate, lambda: shuffle_py.generate_password(config)):
+    try:
+        check()
+        assert False
+    except ValueError as e:
+        assert isinstance(e, shuffle_py.InvalidConfigError)
+
+try:
+    config.set_charset("digit", True)
+    assert False
+except shuffle_py.InvalidConfigError:
+    pass
+"#);
+    }
+
+    #[test]
+    fn test_generate_batch() {
+        run(r#"
+config = shuffle_py.PasswordConfig(3, include_lowercase=False, include_uppercase=False)
+passwords = shuffle_py.generate_batch(config, 1000, threads=2, unique=True)
+assert len(set(passwords)) == 1000
+
+try:
+    shuffle_py.generate_batch(config, 1001, unique=True)
+    assert False
+except shuffle_py.GenerationError:
+    pass
+"#);
+    }
+}
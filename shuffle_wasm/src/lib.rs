@@ -0,0 +1,262 @@
+//! WebAssembly bindings of `shuffle_core`, built with `wasm-bindgen`.
+//!
+//! The generation logic is the one of `shuffle_core`: the RNG backend is seeded from
+//! `crypto.getRandomValues` through the JS backend of `getrandom`.
+//!
+//! ```js
+//! import { PasswordConfig, generate } from "shuffle_wasm";
+//!
+//! const config = new PasswordConfig(20);
+//! config.braces = true;
+//! config.excluded = "0O1Il5S";
+//! config.validate();                 // throws on an invalid configuration
+//! console.log(config.entropyBits); // throws too on an invalid configuration
+//! console.log(generate(config));
+//! ```
+//!
+//! The password is handed to JavaScript as a string: unlike a `SecretString`, it can
+//! not be wiped from memory once returned.
+
+use shuffle_core::config::PasswordConfigError;
+use shuffle_core::{charset, try_generate};
+use wasm_bindgen::prelude::*;
+
+/// Configuration for password generation, see `shuffle_core::PasswordConfig`
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct PasswordConfig(shuffle_core::PasswordConfig);
+
+#[wasm_bindgen]
+impl PasswordConfig {
+    /// Creates a configuration with the default character sets, throws if `length` is 0
+    #[wasm_bindgen(constructor)]
+    pub fn new(length: usize) -> Result<PasswordConfig, JsError> {
+        Ok(Self(shuffle_core::PasswordConfig::new(length)?))
+    }
+
+    /// Length of the password to generate
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.length
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_length(&mut self, length: usize) {
+        self.0.length = length;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn lowercase(&self) -> bool {
+        self.0.charsets.is_enabled(charset::LOWERCASE)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_lowercase(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::LOWERCASE, include);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn uppercase(&self) -> bool {
+        self.0.charsets.is_enabled(charset::UPPERCASE)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_uppercase(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::UPPERCASE, include);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn digits(&self) -> bool {
+        self.0.charsets.is_enabled(charset::DIGITS)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_digits(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::DIGITS, include);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn braces(&self) -> bool {
+        self.0.charsets.is_enabled(charset::BRACES)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_braces(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::BRACES, include);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn punctuation(&self) -> bool {
+        self.0.charsets.is_enabled(charset::PUNCTUATION)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_punctuation(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::PUNCTUATION, include);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn quotes(&self) -> bool {
+        self.0.charsets.is_enabled(charset::QUOTES)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_quotes(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::QUOTES, include);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dashes(&self) -> bool {
+        self.0.charsets.is_enabled(charset::DASHES)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_dashes(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::DASHES, include);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn math(&self) -> bool {
+        self.0.charsets.is_enabled(charset::MATH)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_math(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::MATH, include);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn logograms(&self) -> bool {
+        self.0.charsets.is_enabled(charset::LOGOGRAMS)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_logograms(&mut self, include: bool) {
+        self.0.charsets.set_enabled(charset::LOGOGRAMS, include);
+    }
+
+    /// Characters never used in the password
+    #[wasm_bindgen(getter)]
+    pub fn excluded(&self) -> String {
+        self.0.excluded.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_excluded(&mut self, excluded: String) {
+        self.0.excluded = excluded;
+    }
+
+    /// Characters added to the alphabet
+    #[wasm_bindgen(getter)]
+    pub fn included(&self) -> String {
+        self.0.included.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_included(&mut self, included: String) {
+        self.0.included = included;
+    }
+
+    /// Maximum number of identical consecutive characters, `undefined` for no limit
+    #[wasm_bindgen(getter, js_name = maxConsecutive)]
+    pub fn max_consecutive(&self) -> Option<usize> {
+        self.0.max_consecutive
+    }
+
+    #[wasm_bindgen(setter, js_name = maxConsecutive)]
+    pub fn set_max_consecutive(&mut self, max: Option<usize>) {
+        self.0.max_consecutive = max;
+    }
+
+    /// Maximum length of ascending or descending runs, `undefined` for no limit
+    #[wasm_bindgen(getter, js_name = maxSequence)]
+    pub fn max_sequence(&self) -> Option<usize> {
+        self.0.max_sequence
+    }
+
+    #[wasm_bindgen(setter, js_name = maxSequence)]
+    pub fn set_max_sequence(&mut self, max: Option<usize>) {
+        self.0.max_sequence = max;
+    }
+
+    /// Maximum number of occurrences of any character, `undefined` for no limit
+    #[wasm_bindgen(getter, js_name = maxOccurrences)]
+    pub fn max_occurrences(&self) -> Option<usize> {
+        self.0.max_occurrences
+    }
+
+    #[wasm_bindgen(setter, js_name = maxOccurrences)]
+    pub fn set_max_occurrences(&mut self, max: Option<usize>) {
+        self.0.max_occurrences = max;
+    }
+
+    /// Enables or disables a character class by name, throws if no class has this name
+    #[wasm_bindgen(js_name = setCharset)]
+    pub fn set_charset(&mut self, name: &str, include: bool) -> Result<(), JsError> {
+        self.check_known(name)?;
+        self.0.charsets.set_enabled(name, include);
+        Ok(())
+    }
+
+    /// Registers a custom character class and enables it
+    #[wasm_bindgen(js_name = addCustomCharset)]
+    pub fn add_custom_charset(&mut self, name: &str, chars: &str) {
+        self.0.charsets.register(name, chars);
+        self.0.charsets.set_enabled(name, true);
+    }
+
+    /// Sets the minimum number of characters of a class, throws if no class has this name
+    #[wasm_bindgen(js_name = setMinCount)]
+    pub fn set_min_count(&mut self, name: &str, min: usize) -> Result<(), JsError> {
+        self.check_known(name)?;
+        self.0.charsets.set_min(name, min);
+        Ok(())
+    }
+
+    /// Sets the maximum number of characters of a class, throws if no class has this name
+    #[wasm_bindgen(js_name = setMaxCount)]
+    pub fn set_max_count(&mut self, name: &str, max: usize) -> Result<(), JsError> {
+        self.check_known(name)?;
+        self.0.charsets.set_max(name, Some(max));
+        Ok(())
+    }
+
+    /// Throws an `Error` describing the problem if the configuration is invalid
+    pub fn validate(&self) -> Result<(), JsError> {
+        Ok(self.0.validate()?)
+    }
+
+    /// Entropy of a generated password in bits, see `PasswordConfig::entropy_bits`.
+    /// Throws like `validate` if the configuration is invalid
+    #[wasm_bindgen(getter, js_name = entropyBits)]
+    pub fn entropy_bits(&self) -> Result<f64, JsError> {
+        self.0.validate()?;
+        Ok(self.0.entropy_bits())
+    }
+}
+
+impl PasswordConfig {
+    /// Checked before changing a class, so that a caught error leaves the configuration valid
+    fn check_known(&self, name: &str) -> Result<(), PasswordConfigError> {
+        match self.0.charsets.get(name) {
+            Some(_) => Ok(()),
+            None => Err(PasswordConfigError::UnknownCharacterSet {
+                name: name.to_string(),
+            }),
+        }
+    }
+}
+
+/// Generates a password, throws if the configuration is invalid or the constraints
+/// can not be satisfied
+#[wasm_bindgen]
+pub fn generate(config: &PasswordConfig) -> Result<String, JsError> {
+    let password = try_generate(&config.0)?;
+    Ok(password.expose_secret().to_string())
+}
+
+/// Version of shuffle_core
+#[wasm_bindgen]
+pub fn version() -> String {
+    shuffle_core::getversion().to_string()
+}
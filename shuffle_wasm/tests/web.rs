@@ -0,0 +1,49 @@
+//! Run with `wasm-pack test --node shuffle_wasm`, or in a headless browser with
+//! `WASM_BINDGEN_USE_BROWSER=1 wasm-pack test --headless --firefox shuffle_wasm`
+#![cfg(target_arch = "wasm32")]
+
+use shuffle_wasm::{generate, PasswordConfig};
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn test_generate() {
+    let mut config = PasswordConfig::new(24).unwrap();
+    config.set_excluded("0O1Il5S".to_string());
+    let password = generate(&config).unwrap();
+
+    assert_eq!(password.chars().count(), 24);
+    assert!(!password.contains(|c| "0O1Il5S".contains(c)));
+}
+
+#[wasm_bindgen_test]
+fn test_passwords_differ() {
+    // both are drawn from crypto.getRandomValues
+    let config = PasswordConfig::new(32).unwrap();
+
+    assert_ne!(generate(&config).unwrap(), generate(&config).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_config() {
+    let mut config = PasswordConfig::new(12).unwrap();
+    for name in ["lowercase", "uppercase", "digits"] {
+        config.set_charset(name, false).unwrap();
+    }
+
+    assert!(PasswordConfig::new(0).is_err());
+    assert!(config.set_charset("digit", true).is_err());
+    assert!(config.validate().is_err());
+    assert!(generate(&config).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_entropy_bits() {
+    let mut config = PasswordConfig::new(10).unwrap();
+    config.set_lowercase(false);
+    config.set_uppercase(false);
+
+    assert!((config.entropy_bits().unwrap() - 10.0 * 10f64.log2()).abs() < 1e-9);
+
+    config.set_digits(false);
+    assert!(config.entropy_bits().is_err());
+}
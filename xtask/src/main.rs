@@ -0,0 +1,166 @@
+//! Development tasks, run with `cargo xtask <task>`:
+//!
+//! - `header` updates the committed `cppadapter/include/shuffle.h`
+//! - `install [--prefix PREFIX] [--destdir DIR]` builds cppadapter in release mode and
+//!   installs its headers, libraries, pkg-config file and CMake package
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+type Result<T> = std::result::Result<T, String>;
+
+const USAGE: &str = "usage: cargo xtask header
+       cargo xtask install [--prefix PREFIX] [--destdir DIR]";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("header") if args.len() == 1 => header(),
+        Some("install") => install(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(e) = result {
+        eprintln!("Error : {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn workspace_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf()
+}
+
+/// What the build of cppadapter produced
+struct Build {
+    /// `OUT_DIR` of the build script, see `cppadapter/build.rs`
+    out_dir: PathBuf,
+    /// The static and shared libraries
+    libraries: Vec<PathBuf>,
+}
+
+/// Builds cppadapter and reads where cargo put its files
+fn build(release: bool, prefix: Option<&Path>) -> Result<Build> {
+    let mut cmd = Command::new(std::env::var("CARGO").unwrap_or("cargo".to_string()));
+    cmd.current_dir(workspace_dir())
+        .args(["build", "--package", "cpp_adapter", "--message-format=json-render-diagnostics"])
+        .stdout(Stdio::piped());
+    if release {
+        cmd.arg("--release");
+    }
+    if let Some(prefix) = prefix {
+        cmd.env("SHUFFLE_PREFIX", prefix);
+    }
+
+    let output = cmd.output().map_err(|e| format!("could not run cargo: {}", e))?;
+    if !output.status.success() {
+        return Err("the build of cppadapter failed".to_string());
+    }
+
+    let mut package_id = None;
+    let mut libraries = Vec::new();
+    let mut out_dirs = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let message: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+        match message["reason"].as_str() {
+            Some("compiler-artifact") if message["target"]["name"] == "cpp_adapter" => {
+                package_id = Some(message["package_id"].clone());
+                libraries = message["filenames"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(PathBuf::from)
+                    .filter(|file| file.extension().is_some_and(|ext| ext != "rlib"))
+                    .collect();
+            }
+            Some("build-script-executed") => {
+                if let Some(out_dir) = message["out_dir"].as_str() {
+                    out_dirs.push((message["package_id"].clone(), PathBuf::from(out_dir)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let package_id = package_id.ok_or("cargo built no cpp_adapter library")?;
+    let out_dir = out_dirs
+        .into_iter()
+        .find(|(id, _)| *id == package_id)
+        .map(|(_, out_dir)| out_dir)
+        .ok_or("the build script of cppadapter did not run")?;
+    Ok(Build { out_dir, libraries })
+}
+
+/// Copies `src` to `dest`, creating the missing directories
+fn copy(src: &Path, dest: &Path) -> Result<()> {
+    std::fs::create_dir_all(dest.parent().unwrap()).map_err(|e| e.to_string())?;
+    std::fs::copy(src, dest)
+        .map(|_| println!("{}", dest.display()))
+        .map_err(|e| format!("could not copy {} to {}: {}", src.display(), dest.display(), e))
+}
+
+fn header() -> Result<()> {
+    let build = build(false, None)?;
+    copy(
+        &build.out_dir.join("include/shuffle.h"),
+        &workspace_dir().join("cppadapter/include/shuffle.h"),
+    )
+}
+
+fn install(args: &[String]) -> Result<()> {
+    let mut prefix = PathBuf::from("/usr/local");
+    let mut destdir = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or(USAGE)?;
+        match arg.as_str() {
+            "--prefix" => prefix = PathBuf::from(value),
+            "--destdir" => destdir = Some(PathBuf::from(value)),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    // the pkg-config and CMake files need an absolute prefix
+    if prefix.is_relative() {
+        prefix = std::env::current_dir().map_err(|e| e.to_string())?.join(prefix);
+    }
+    let root = match destdir {
+        Some(destdir) => destdir.join(prefix.strip_prefix("/").unwrap_or(&prefix)),
+        None => prefix.clone(),
+    };
+
+    let build = build(true, Some(&prefix))?;
+    let out_dir = &build.out_dir;
+    for header in ["shuffle.h", "shuffle_cxx.h", "rust/cxx.h"] {
+        copy(&out_dir.join("include").join(header), &root.join("include").join(header))?;
+    }
+    copy(
+        &out_dir.join("pkgconfig/shuffle.pc"),
+        &root.join("lib/pkgconfig/shuffle.pc"),
+    )?;
+    for file in ["ShuffleConfig.cmake", "ShuffleConfigVersion.cmake"] {
+        copy(
+            &out_dir.join("cmake/Shuffle").join(file),
+            &root.join("lib/cmake/Shuffle").join(file),
+        )?;
+    }
+
+    // the shared library is installed under its SONAME, with a link for the linker
+    let soname = std::fs::read_to_string(out_dir.join("soname")).map_err(|e| e.to_string())?;
+    let shared_suffix = std::env::consts::DLL_SUFFIX;
+    for library in &build.libraries {
+        let name = library.file_name().unwrap().to_string_lossy().into_owned();
+        if cfg!(unix) && name.ends_with(shared_suffix) {
+            copy(library, &root.join("lib").join(&soname))?;
+            #[cfg(unix)]
+            {
+                let link = root.join("lib").join(&name);
+                let _ = std::fs::remove_file(&link);
+                std::os::unix::fs::symlink(&soname, &link).map_err(|e| e.to_string())?;
+                println!("{} -> {}", link.display(), soname);
+            }
+        } else {
+            copy(library, &root.join("lib").join(&name))?;
+        }
+    }
+    Ok(())
+}